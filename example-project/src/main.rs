@@ -1,9 +1,16 @@
 use postgres_from_row::FromRow;
-use postgres_named_parameters::{Query, Statement};
+use postgres_named_parameters::{BulkInsert, Query, Statement};
 
 // Use the postgres-from-row crate to deserialize the rows returned
-// from queries into a struct
-#[derive(FromRow, Debug)]
+// from queries into a struct.
+//
+// There are various approaches for bulk inserts. Many ORMs take the approach
+// of constructing SQL at runtime. This library is centered around SQL that is
+// finalized at compile time, so #[derive(BulkInsert)] takes a different
+// approach: it splits a slice of structs (&[Person]) into one vector per
+// column, and then reassembles them in Postgres using UNNEST.
+#[derive(FromRow, BulkInsert, Debug)]
+#[bulk_insert(table = "Person")]
 struct Person {
     first_name: String,
     last_name: String,
@@ -53,38 +60,7 @@ struct DeletePerson {
     id: i32
 }
 
-fn bulk_insert_people(
-    db: &mut impl postgres::GenericClient,
-    people: Vec<Person>,
-) -> Result<u64, postgres::Error> {
-    // There are various approaches for bulk queries like this one. Many ORMs
-    // take the approach of constructing SQL at runtime. This library is
-    // centered around SQL that is finalized at compile time, so we use a
-    // different approach: we can split a vector of structs (Vec<Person>) into
-    // one vector per column, and then reassemble them in Postgres.
-    #[derive(Statement)]
-    #[statement(sql = "
-        INSERT INTO Person (first_name, last_name, hobby, alive)
-        SELECT *
-        FROM UNNEST(@first_names::TEXT[], @last_names::TEXT[], @hobbies::TEXT[], @alive_statuses::BOOL[])
-    ")]
-    struct InsertPeople {
-        first_names: Vec<String>,
-        last_names: Vec<String>,
-        hobbies: Vec<Option<String>>,
-        alive_statuses: Vec<bool>,
-    }
-
-    InsertPeople {
-        first_names: people.iter().map(|p| p.first_name.clone()).collect(),
-        last_names: people.iter().map(|p| p.last_name.clone()).collect(),
-        hobbies: people.iter().map(|p| p.hobby.clone()).collect(),
-        alive_statuses: people.iter().map(|p| p.alive).collect(),
-    }
-    .execute_statement(db)
-}
-
-fn main() -> Result<(), postgres::Error> {
+fn main() -> Result<(), postgres_named_parameters::Error> {
     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
         .unwrap_or("host=localhost user=postgres".to_owned());
     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
@@ -111,7 +87,7 @@ fn main() -> Result<(), postgres::Error> {
         },
     ];
 
-    bulk_insert_people(&mut db, people_to_insert)?;
+    Person::insert_many(&mut db, &people_to_insert)?;
 
     let people = GetPeople {
         alive: true,
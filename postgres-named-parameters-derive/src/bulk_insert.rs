@@ -0,0 +1,198 @@
+use attribute_derive::FromAttr;
+use quote::quote;
+use syn::{DeriveInput, GenericArgument, PathArguments, Type};
+
+#[derive(FromAttr)]
+#[attribute(ident = bulk_insert)]
+struct BulkInsertTraitHelperAttribute {
+    #[attribute(example = r#""Person""#)]
+    table: String,
+    #[attribute(example = r#""(first_name, last_name) DO NOTHING""#)]
+    on_conflict: Option<String>,
+    redact: bool,
+}
+
+#[derive(FromAttr)]
+#[attribute(ident = bulk_insert)]
+struct BulkInsertFieldHelperAttribute {
+    #[attribute(example = r#""TIMESTAMPTZ""#)]
+    sql_type: Option<String>,
+    #[attribute(example = r#""first_name""#)]
+    column: Option<String>,
+    redact: bool,
+}
+
+pub fn derive_bulk_insert_impl(ast: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let args = BulkInsertTraitHelperAttribute::from_attributes(&ast.attrs)?;
+    let syn::Data::Struct(struct_ast) = ast.data else {
+        return Err(syn::Error::new(
+            ast.ident.span(),
+            "#[derive(BulkInsert)] can only be used on structs",
+        ));
+    };
+    let syn::Fields::Named(named_fields) = struct_ast.fields else {
+        return Err(syn::Error::new(
+            ast.ident.span(),
+            "#[derive(BulkInsert)] can only be used on structs with named fields",
+        ));
+    };
+    if named_fields.named.is_empty() {
+        return Err(syn::Error::new(
+            ast.ident.span(),
+            "#[derive(BulkInsert)] requires at least one field to insert",
+        ));
+    }
+
+    let mut columns = vec![];
+    let mut array_casts = vec![];
+    let mut field_names = vec![];
+    let mut redact = args.redact;
+    for (index, field) in named_fields.named.iter().enumerate() {
+        let field_args = BulkInsertFieldHelperAttribute::from_attributes(&field.attrs)?;
+        let field_name = field.ident.clone().expect("named fields have identifiers");
+        if is_array(&field.ty) {
+            // UNNEST flattens an array of arrays rather than returning one
+            // array per row, so these columns can't be inserted this way
+            let err = format!(
+                r#"#[derive(BulkInsert)] does not support array fields such as "{}", since UNNEST cannot split an array of arrays into one array per row. Insert these rows using a #[derive(Statement)] instead"#,
+                field_name,
+            );
+            return Err(syn::Error::new_spanned(&field.ty, err));
+        }
+        let sql_type = match field_args.sql_type {
+            Some(sql_type) => sql_type,
+            None => match infer_sql_type(&field.ty) {
+                Some(sql_type) => sql_type.to_owned(),
+                None => {
+                    let err = format!(
+                        r#"#[derive(BulkInsert)] cannot infer the SQL type of the field "{}". Specify it using #[bulk_insert(sql_type = "...")]"#,
+                        field_name,
+                    );
+                    return Err(syn::Error::new_spanned(&field.ty, err));
+                }
+            },
+        };
+        redact |= field_args.redact;
+        columns.push(field_args.column.unwrap_or_else(|| field_name.to_string()));
+        array_casts.push(format!("${}::{}[]", index + 1, sql_type));
+        field_names.push(field_name);
+    }
+
+    let mut sql = format!(
+        "INSERT INTO {} ({}) SELECT * FROM UNNEST({})",
+        args.table,
+        columns.join(", "),
+        array_casts.join(", "),
+    );
+    if let Some(on_conflict) = args.on_conflict {
+        sql.push_str(" ON CONFLICT ");
+        sql.push_str(&on_conflict);
+    }
+
+    let generics = ast.generics;
+    let ident = ast.ident;
+    let where_clause = &generics.where_clause;
+    let body = crate::util::wrap_with_tracing(
+        &ident,
+        "insert_many",
+        &sql,
+        "db.response.affected_rows",
+        quote!(|rows_affected: &u64| *rows_affected),
+        crate::util::wrap_with_context(
+            &ident,
+            &sql,
+            redact,
+            quote! {
+                // Split the rows into one array per column so that
+                // Postgres can reassemble them using UNNEST
+                Ok(connection.execute(
+                    #sql,
+                    &[
+                        #(&rows.iter().map(|row| &row.#field_names).collect::<Vec<_>>()),*
+                    ],
+                )?)
+            },
+        ),
+    );
+
+    let output = quote! {
+        #[automatically_derived]
+        impl #generics postgres_named_parameters::BulkInsert for #ident #generics #where_clause {
+            fn insert_many(
+                connection: &mut impl postgres_named_parameters::postgres::GenericClient,
+                rows: &[Self],
+            ) -> Result<u64, postgres_named_parameters::Error> {
+                #body
+            }
+        }
+    };
+    Ok(output)
+}
+
+/// Infer the SQL type of a column from the Rust type of the corresponding
+/// field, looking through `Option`s and references. Only the types that the
+/// `postgres` crate supports without enabling any of its features are
+/// recognized.
+fn infer_sql_type(ty: &Type) -> Option<&'static str> {
+    match ty {
+        Type::Reference(reference) => infer_sql_type(&reference.elem),
+        Type::Slice(slice) if is_u8(&slice.elem) => Some("BYTEA"),
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last()?;
+            match segment.ident.to_string().as_str() {
+                "Option" => infer_sql_type(get_single_type_argument(&segment.arguments)?),
+                "Vec" if is_u8(get_single_type_argument(&segment.arguments)?) => Some("BYTEA"),
+                "bool" => Some("BOOL"),
+                "i8" => Some("\"char\""),
+                "i16" => Some("INT2"),
+                "i32" => Some("INT4"),
+                "i64" => Some("INT8"),
+                "u32" => Some("OID"),
+                "f32" => Some("FLOAT4"),
+                "f64" => Some("FLOAT8"),
+                "String" | "str" => Some("TEXT"),
+                "SystemTime" => Some("TIMESTAMPTZ"),
+                "IpAddr" => Some("INET"),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Whether the field holds an array (e.g. a `Vec<String>` or `&[i32]`),
+/// looking through `Option`s and references. `Vec<u8>` and `&[u8]` are
+/// `BYTEA` rather than arrays.
+fn is_array(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => is_array(&reference.elem),
+        Type::Slice(slice) => !is_u8(&slice.elem),
+        Type::Array(_) => true,
+        Type::Path(type_path) => {
+            let Some(segment) = type_path.path.segments.last() else {
+                return false;
+            };
+            let argument = get_single_type_argument(&segment.arguments);
+            match segment.ident.to_string().as_str() {
+                "Option" => argument.is_some_and(is_array),
+                "Vec" => argument.is_some_and(|argument| !is_u8(argument)),
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+fn get_single_type_argument(arguments: &PathArguments) -> Option<&Type> {
+    let PathArguments::AngleBracketed(arguments) = arguments else {
+        return None;
+    };
+    match arguments.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+fn is_u8(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.is_ident("u8"))
+}
@@ -0,0 +1,106 @@
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Expr, LitStr, Token};
+
+/// A single `"constraint_name" => Error::Variant` mapping given to the
+/// `on_constraint(...)` parameter of `#[statement(...)]`.
+struct ConstraintMapping {
+    constraint: LitStr,
+    violation: Expr,
+}
+
+impl Parse for ConstraintMapping {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let constraint = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let violation = input.parse()?;
+        Ok(ConstraintMapping {
+            constraint,
+            violation,
+        })
+    }
+}
+
+/// The constraint violations to classify, as specified by the
+/// `on_constraint(...)` parameters of `#[statement(...)]`.
+pub struct ConstraintMappings {
+    mappings: Vec<ConstraintMapping>,
+    error_type: syn::Path,
+}
+
+impl ConstraintMappings {
+    pub fn parse(arguments: Vec<proc_macro2::TokenStream>) -> syn::Result<Option<ConstraintMappings>> {
+        let mut mappings = vec![];
+        for argument in arguments {
+            let parser = Punctuated::<ConstraintMapping, Token![,]>::parse_terminated;
+            mappings.extend(syn::parse::Parser::parse2(parser, argument)?);
+        }
+        let Some(first) = mappings.first() else {
+            return Ok(None);
+        };
+
+        // The error type is the path to the variant without the variant's name
+        // (e.g. `PersonError` for `PersonError::EmailTaken`)
+        let error_type = error_type(&first.violation)?;
+        for mapping in &mappings[1..] {
+            if error_type != self::error_type(&mapping.violation)? {
+                return Err(syn::Error::new(
+                    mapping.violation.span(),
+                    "All the variants given to `on_constraint` must belong to the same enum",
+                ));
+            }
+        }
+        Ok(Some(ConstraintMappings {
+            mappings,
+            error_type,
+        }))
+    }
+
+    /// The error type returned by the generated methods.
+    pub fn statement_error_type(&self) -> proc_macro2::TokenStream {
+        let error_type = &self.error_type;
+        quote!(postgres_named_parameters::StatementError<#error_type>)
+    }
+
+    /// Wrap the body of a generated method so that any constraint violation it
+    /// returns is classified.
+    pub fn wrap(&self, body: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let constraints = self.mappings.iter().map(|mapping| &mapping.constraint);
+        let violations = self.mappings.iter().map(|mapping| &mapping.violation);
+        quote! {
+            (#body).map_err(|error| {
+                let violation = match postgres_named_parameters::internal::constraint_for_derive_macro::violated_constraint(&error) {
+                    #(Some(#constraints) => Some(#violations),)*
+                    _ => None,
+                };
+                postgres_named_parameters::internal::constraint_for_derive_macro::statement_error(error, violation)
+            })
+        }
+    }
+}
+
+fn error_type(violation: &Expr) -> syn::Result<syn::Path> {
+    let path = match violation {
+        Expr::Path(path) => Some(&path.path),
+        Expr::Call(call) => match &*call.func {
+            Expr::Path(path) => Some(&path.path),
+            _ => None,
+        },
+        Expr::Struct(expr_struct) => Some(&expr_struct.path),
+        _ => None,
+    };
+    match path {
+        Some(path) if path.segments.len() >= 2 => {
+            let mut error_type = path.clone();
+            error_type.segments.pop();
+            error_type.segments.pop_punct();
+            Ok(error_type)
+        }
+        _ => Err(syn::Error::new(
+            violation.span(),
+            "Expected an enum variant, e.g. `PersonError::EmailTaken`",
+        )),
+    }
+}
@@ -1,12 +1,17 @@
 #![doc = include_str!("../README.md")] 
 use syn::DeriveInput;
 
+mod bulk_insert;
+mod constraint;
 mod numberify;
+mod paginate;
 mod query;
+mod row_columns;
 mod statement;
+mod transaction;
 mod util;
 
-#[proc_macro_derive(Query, attributes(query))]
+#[proc_macro_derive(Query, attributes(query, param))]
 pub fn derive_query(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast = syn::parse_macro_input!(input as DeriveInput);
     query::derive_query_impl(ast)
@@ -14,10 +19,34 @@ pub fn derive_query(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         .into()
 }
 
-#[proc_macro_derive(Statement, attributes(statement))]
+#[proc_macro_derive(Statement, attributes(statement, param))]
 pub fn derive_statement(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast = syn::parse_macro_input!(input as DeriveInput);
     statement::derive_statement_impl(ast)
         .unwrap_or_else(|err| err.into_compile_error())
         .into()
 }
+
+#[proc_macro_derive(BulkInsert, attributes(bulk_insert))]
+pub fn derive_bulk_insert(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    bulk_insert::derive_bulk_insert_impl(ast)
+        .unwrap_or_else(|err| err.into_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(RowColumns, attributes(from_row))]
+pub fn derive_row_columns(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    row_columns::derive_row_columns_impl(ast)
+        .unwrap_or_else(|err| err.into_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(Transaction, attributes(transaction))]
+pub fn derive_transaction(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    transaction::derive_transaction_impl(ast)
+        .unwrap_or_else(|err| err.into_compile_error())
+        .into()
+}
@@ -1,10 +1,26 @@
 pub fn numberify(query: String, parameters: Vec<String>) -> Result<String, String> {
+    let (fragments, indices) = split_parameters(query, parameters)?;
+    let mut output = fragments[0].clone();
+    for (index, fragment) in indices.iter().zip(&fragments[1..]) {
+        output.push_str(&format!("${}", index + 1));
+        output.push_str(fragment);
+    }
+    Ok(output)
+}
+
+/// Split the SQL at each named parameter, returning the SQL fragments between
+/// the parameters (with any escaped '@@' unescaped) and the index of each
+/// parameter in `parameters`. There is always one more fragment than there are
+/// parameters.
+pub fn split_parameters(query: String, parameters: Vec<String>) -> Result<(Vec<String>, Vec<usize>), String> {
     let mut input = query.chars().peekable();
-    let mut output = String::new();
+    let mut fragments = vec![String::new()];
+    let mut indices = vec![];
 
     loop {
+        let output = fragments.last_mut().expect("there is always a fragment");
         match input.next() {
-            None => return Ok(output),
+            None => return Ok((fragments, indices)),
             Some('@') => {
                 match input.peek() {
                     None => return Err("Input SQL should not end with '@'. If a single literal '@' is desired, it should be doubled ('@@')".to_owned()),
@@ -25,8 +41,8 @@ pub fn numberify(query: String, parameters: Vec<String>) -> Result<String, Strin
 
                         match parameters.iter().position(|field| field_name == *field) {
                             Some(raw_index) => {
-                                let sql_index = raw_index + 1;
-                                output.push_str(&format!("${}", sql_index));
+                                indices.push(raw_index);
+                                fragments.push(String::new());
                             },
                             None => return Err(format!(
                                 r#"The provided SQL contains "@{}", but there is no matching field in the struct with the name "{}""#,
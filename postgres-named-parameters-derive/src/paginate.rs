@@ -0,0 +1,246 @@
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Expr, ExprLit, Lit};
+
+const PAGINATE_USAGE: &str =
+    r#"Expected the `paginate` parameter to be `keyset(order_by = "...")`, `offset` or `offset(order_by = "...")`, optionally followed by `total` (e.g. `offset(total)`)"#;
+
+/// How the rows of a query are split into pages, as specified by the
+/// `paginate` parameter of `#[query(...)]`.
+pub enum Pagination {
+    Keyset { order_by: Vec<String>, total: bool },
+    Offset { order_by: Option<Vec<String>>, total: bool },
+}
+
+impl Pagination {
+    pub fn parse(expr: &Expr) -> syn::Result<Pagination> {
+        let (function, args) = match expr {
+            Expr::Path(path) => (path, vec![]),
+            Expr::Call(call) => match &*call.func {
+                Expr::Path(path) => (path, call.args.iter().collect()),
+                _ => return Err(syn::Error::new(call.func.span(), PAGINATE_USAGE)),
+            },
+            _ => return Err(syn::Error::new(expr.span(), PAGINATE_USAGE)),
+        };
+
+        let mut order_by = None;
+        let mut order_by_span = expr.span();
+        let mut total = false;
+        for arg in args {
+            match arg {
+                Expr::Path(path) if path.path.is_ident("total") => total = true,
+                Expr::Assign(assign) if matches!(&*assign.left, Expr::Path(path) if path.path.is_ident("order_by")) => {
+                    let Expr::Lit(ExprLit {
+                        lit: Lit::Str(columns),
+                        ..
+                    }) = &*assign.right
+                    else {
+                        return Err(syn::Error::new(
+                            assign.right.span(),
+                            "Expected `order_by` to be a string literal containing a comma separated list of columns",
+                        ));
+                    };
+                    order_by_span = columns.span();
+                    order_by = Some(
+                        columns
+                            .value()
+                            .split(',')
+                            .map(|column| column.trim().to_owned())
+                            .filter(|column| !column.is_empty())
+                            .collect::<Vec<_>>(),
+                    );
+                }
+                _ => return Err(syn::Error::new(arg.span(), PAGINATE_USAGE)),
+            }
+        }
+
+        if function.path.is_ident("keyset") {
+            match order_by {
+                Some(order_by) if !order_by.is_empty() => {
+                    // The rows after the cursor are found by comparing the
+                    // columns as a tuple, which only works in ascending order
+                    if let Some(column) = order_by.iter().find(|column| !is_plain_identifier(column)) {
+                        return Err(syn::Error::new(
+                            order_by_span,
+                            format!(
+                                "Keyset pagination can only order by plain column names in ascending order, but `order_by` contains `{}`",
+                                column
+                            ),
+                        ));
+                    }
+                    Ok(Pagination::Keyset { order_by, total })
+                }
+                _ => Err(syn::Error::new(
+                    expr.span(),
+                    r#"Keyset pagination requires the columns to order by, e.g. `keyset(order_by = "created_at, id")`"#,
+                )),
+            }
+        } else if function.path.is_ident("offset") {
+            match order_by {
+                Some(order_by) if order_by.is_empty() => Err(syn::Error::new(
+                    expr.span(),
+                    r#"Expected `order_by` to list the columns to order by, e.g. `offset(order_by = "created_at, id")`"#,
+                )),
+                order_by => Ok(Pagination::Offset { order_by, total }),
+            }
+        } else {
+            Err(syn::Error::new(function.span(), PAGINATE_USAGE))
+        }
+    }
+
+    /// The SQL which `query_page` runs, wrapping the given (numbered) SQL in
+    /// a query that fetches a single page. Keyset pagination runs one query
+    /// for the first page and another for the pages after it.
+    pub fn page_sql(&self, nested_sql: &str, parameter_count: usize) -> Vec<String> {
+        match self {
+            Pagination::Keyset { order_by, .. } => {
+                let columns = order_by.join(", ");
+                let cursor_parameters = (0..order_by.len())
+                    .map(|index| format!("${}", parameter_count + index + 1))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let first_page_sql = format!(
+                    "SELECT * FROM ({}\n) _page ORDER BY {} LIMIT ${}",
+                    nested_sql,
+                    columns,
+                    parameter_count + 1,
+                );
+                let next_page_sql = format!(
+                    "SELECT * FROM ({}\n) _page WHERE ({}) > ({}) ORDER BY {} LIMIT ${}",
+                    nested_sql,
+                    columns,
+                    cursor_parameters,
+                    columns,
+                    parameter_count + order_by.len() + 1,
+                );
+                vec![first_page_sql, next_page_sql]
+            }
+            Pagination::Offset { order_by, .. } => {
+                // Postgres doesn't promise to keep the order of a subquery, so
+                // the rows are only reliably ordered by an outer ORDER BY
+                let order_by = match order_by {
+                    Some(order_by) => format!(" ORDER BY {}", order_by.join(", ")),
+                    None => String::new(),
+                };
+                vec![format!(
+                    "SELECT * FROM ({}\n) _page{} LIMIT ${} OFFSET ${}",
+                    nested_sql,
+                    order_by,
+                    parameter_count + 1,
+                    parameter_count + 2,
+                )]
+            }
+        }
+    }
+
+    /// Generate the body of `query_page`, which runs the SQL returned by
+    /// [Pagination::page_sql]. If `tag` is set, the SQL is passed through the
+    /// runtime tag hook.
+    pub fn query_page_body(
+        &self,
+        nested_sql: &str,
+        parameter_count: usize,
+        parameter_list: &proc_macro2::TokenStream,
+        tag: bool,
+    ) -> proc_macro2::TokenStream {
+        let page_sql = self.page_sql(nested_sql, parameter_count);
+        let (fetch_page, total) = match self {
+            Pagination::Keyset { order_by, total } => {
+                let first_page_sql = crate::util::sql_expression(&page_sql[0], tag);
+                let next_page_sql = crate::util::sql_expression(&page_sql[1], tag);
+                let fetch_page = quote! {
+                    postgres_named_parameters::internal::pagination_for_derive_macro::query_keyset_page::<Self::Row, _>(
+                        connection,
+                        #first_page_sql,
+                        #next_page_sql,
+                        #parameter_list,
+                        &[#(#order_by),*],
+                        cursor,
+                        limit,
+                    )
+                };
+                (fetch_page, *total)
+            }
+            Pagination::Offset { total, .. } => {
+                let sql = crate::util::sql_expression(&page_sql[0], tag);
+                let fetch_page = quote! {
+                    postgres_named_parameters::internal::pagination_for_derive_macro::query_offset_page::<Self::Row, _>(
+                        connection,
+                        #sql,
+                        #parameter_list,
+                        cursor,
+                        limit,
+                    )
+                };
+                (fetch_page, *total)
+            }
+        };
+
+        if total {
+            quote! {
+                let mut page = #fetch_page?;
+                page.total = Some(postgres_named_parameters::Query::query_count(self, connection)?);
+                Ok(page)
+            }
+        } else {
+            fetch_page
+        }
+    }
+
+    pub fn cursor_type(&self) -> proc_macro2::TokenStream {
+        match self {
+            Pagination::Keyset { .. } => quote!(postgres_named_parameters::KeysetCursor),
+            Pagination::Offset { .. } => quote!(postgres_named_parameters::OffsetCursor),
+        }
+    }
+}
+
+/// Whether the given column is an unquoted identifier (e.g. `created_at`),
+/// rather than e.g. `created_at DESC` or `lower(name)`.
+fn is_plain_identifier(column: &str) -> bool {
+    let mut chars = column.chars();
+    chars
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(tokens: &str) -> syn::Result<Pagination> {
+        Pagination::parse(&syn::parse_str(tokens).unwrap())
+    }
+
+    #[test]
+    fn parses_keyset_order_by() {
+        let Ok(Pagination::Keyset { order_by, total }) = parse(r#"keyset(order_by = "created_at, id", total)"#) else {
+            panic!("expected keyset pagination");
+        };
+        assert_eq!(order_by, ["created_at", "id"]);
+        assert!(total);
+    }
+
+    #[test]
+    fn rejects_keyset_order_by_with_directions_or_expressions() {
+        for order_by in ["created_at DESC, id", "id ASC", "lower(name), id", "Person.id", "\"id\""] {
+            let err = parse(&format!("keyset(order_by = {:?})", order_by))
+                .err()
+                .expect("order_by should be rejected");
+            assert!(err.to_string().contains("plain column names"), "{}", err);
+        }
+        assert!(parse(r#"keyset(order_by = "")"#).is_err());
+    }
+
+    #[test]
+    fn allows_offset_order_by_with_directions() {
+        let Ok(Pagination::Offset { order_by, total }) = parse(r#"offset(order_by = "created_at DESC, id")"#) else {
+            panic!("expected offset pagination");
+        };
+        assert_eq!(order_by.unwrap(), ["created_at DESC", "id"]);
+        assert!(!total);
+        assert!(matches!(parse("offset(total)"), Ok(Pagination::Offset { order_by: None, total: true })));
+        assert!(parse("pages").is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use attribute_derive::FromAttr;
 use quote::quote;
-use syn::{DeriveInput, Type};
+use syn::{DeriveInput, Expr, Type};
 
 #[derive(FromAttr)]
 #[attribute(ident = query)]
@@ -9,6 +9,14 @@ struct QueryTraitHelperAttribute {
     sql: String,
     #[attribute(example = "crate::my_database_tables::Person")]
     row: Type,
+    #[attribute(example = r#"keyset(order_by = "created_at, id")"#)]
+    paginate: Option<Expr>,
+    #[attribute(example = r#""2s""#)]
+    timeout: Option<String>,
+    requires_transaction: bool,
+    tag: bool,
+    redact: bool,
+    read_only: bool,
 }
 
 pub fn derive_query_impl(ast: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
@@ -19,58 +27,216 @@ pub fn derive_query_impl(ast: DeriveInput) -> syn::Result<proc_macro2::TokenStre
             "#[derive(Query)] can only be used on structs",
         ));
     };
+    let pagination = args
+        .paginate
+        .as_ref()
+        .map(crate::paginate::Pagination::parse)
+        .transpose()?;
+    let timeout = crate::util::parse_timeout(args.timeout.as_deref(), &ast.ident)?;
     let named_parameters = crate::util::get_field_names(&struct_ast);
     let parameter_list = crate::util::get_parameter_list(&struct_ast);
-    let transformed_sql = match crate::numberify::numberify(args.sql, named_parameters) {
+    let redacted = crate::util::get_redacted_parameters(&struct_ast, args.redact)?;
+    let redact = !redacted.is_empty();
+    let transformed_sql = match crate::numberify::numberify(args.sql.clone(), named_parameters.clone()) {
         Ok(sql) => sql,
         Err(err) => {
             let err = format!("Error with SQL provided to #[derive(Query)]: {}", err);
             return Err(syn::Error::new(ast.ident.span(), err));
         }
     };
+    let tag = args.tag || cfg!(feature = "sql-tags");
+    let transformed_sql = if tag {
+        crate::util::tag_sql(&transformed_sql, &ast.ident)
+    } else {
+        transformed_sql
+    };
+
+    let requires = if args.requires_transaction {
+        quote!(postgres_named_parameters::connection::InTransaction)
+    } else {
+        quote!(postgres_named_parameters::connection::AnyConnection)
+    };
+
+    let read_only = args.read_only;
 
     let generics = ast.generics;
     let ident = ast.ident;
     let where_clause = &generics.where_clause;
     let row_type = args.row;
+    // Wrap the query so we can check whether it returns any rows or count how
+    // many rows it returns. The closing parentheses go on a new line in case
+    // the query ends with a `--` comment.
+    let nested_sql = crate::util::trim_sql_terminator(&transformed_sql);
+    let exists_sql = format!("SELECT EXISTS ({}\n)", nested_sql);
+    let count_sql = format!("SELECT count(*) FROM ({}\n) _", nested_sql);
+
+    let sql = crate::util::sql_expression(&transformed_sql, tag);
+    let exists_sql_expression = crate::util::sql_expression(&exists_sql, tag);
+    let count_sql_expression = crate::util::sql_expression(&count_sql, tag);
 
-    let output = quote! {
+    let wrap_body = |sql: &str, body| {
+        crate::util::wrap_with_context(
+            &ident,
+            sql,
+            redact,
+            crate::util::wrap_with_read_only(read_only, crate::util::wrap_with_timeout(timeout, body)),
+        )
+    };
+    let trace = |function, sql: &str, rows_returned, body| {
+        crate::util::wrap_with_tracing(
+            &ident,
+            function,
+            sql,
+            "db.response.returned_rows",
+            rows_returned,
+            body,
+        )
+    };
+    let query_all_body = trace(
+        "query_all",
+        &transformed_sql,
+        quote!(|rows: &Vec<Self::Row>| rows.len() as u64),
+        wrap_body(&transformed_sql, quote! {
+            let rows = connection.query(#sql, #parameter_list)?;
+            rows
+                .iter()
+                .map(postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<Self::Row, _>)
+                .collect()
+        }),
+    );
+    let query_opt_body = trace(
+        "query_opt",
+        &transformed_sql,
+        quote!(|row: &Option<Self::Row>| row.is_some() as u64),
+        wrap_body(&transformed_sql, quote! {
+            postgres_named_parameters::internal::wrapper_for_derive_macro::query_opt::<Self::Row, _>(connection, #sql, #parameter_list)
+        }),
+    );
+    let query_one_body = trace(
+        "query_one",
+        &transformed_sql,
+        quote!(|_: &Self::Row| 1),
+        wrap_body(&transformed_sql, quote! {
+            postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<Self::Row, _>(connection, #sql, #parameter_list)
+        }),
+    );
+    let query_exists_body = trace(
+        "query_exists",
+        &exists_sql,
+        quote!(|_: &bool| 1),
+        wrap_body(&exists_sql, quote! {
+            postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<bool, _>(connection, #exists_sql_expression, #parameter_list)
+        }),
+    );
+    let query_count_body = trace(
+        "query_count",
+        &count_sql,
+        quote!(|_: &i64| 1),
+        wrap_body(&count_sql, quote! {
+            postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<i64, _>(connection, #count_sql_expression, #parameter_list)
+        }),
+    );
+
+    let mut output = quote! {
         #[automatically_derived]
         impl #generics postgres_named_parameters::Query for #ident #generics #where_clause {
             type Row = #row_type;
+            type Requires = #requires;
+            const READ_ONLY: bool = #read_only;
+            const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(#transformed_sql);
+            const EXISTS_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(#exists_sql);
+            const COUNT_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(#count_sql);
             fn query_all(
                 &self,
-                connection: &mut impl postgres_named_parameters::postgres::GenericClient,
-            ) -> Result<Vec<Self::Row>, postgres_named_parameters::postgres::error::Error> {
-                let rows = connection.query(#transformed_sql, #parameter_list)?;
-                rows
-                    .iter()
-                    .map(postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<Self::Row>)
-                    .collect()
+                connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+            ) -> Result<Vec<Self::Row>, postgres_named_parameters::Error> {
+                #query_all_body
             }
 
             fn query_opt(
                 &self,
-                connection: &mut impl postgres_named_parameters::postgres::GenericClient,
-            ) -> Result<Option<Self::Row>, postgres_named_parameters::postgres::error::Error> {
-                let maybe_row = connection.query_opt(#transformed_sql, #parameter_list)?;
-                match maybe_row {
-                    None => Ok(None),
-                    Some(row) => {
-                        let decoded_row = postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<Self::Row>(&row)?;
-                        Ok(Some(decoded_row))
-                    }
-                }
+                connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+            ) -> Result<Option<Self::Row>, postgres_named_parameters::Error> {
+                #query_opt_body
             }
 
             fn query_one(
                 &self,
-                connection: &mut impl postgres_named_parameters::postgres::GenericClient,
-            ) -> Result<Self::Row, postgres_named_parameters::postgres::error::Error> {
-                let row = connection.query_one(#transformed_sql, #parameter_list)?;
-                postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<Self::Row>(&row)
+                connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+            ) -> Result<Self::Row, postgres_named_parameters::Error> {
+                #query_one_body
+            }
+
+            fn query_exists(
+                &self,
+                connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+            ) -> Result<bool, postgres_named_parameters::Error> {
+                #query_exists_body
+            }
+
+            fn query_count(
+                &self,
+                connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+            ) -> Result<i64, postgres_named_parameters::Error> {
+                #query_count_body
             }
         }
     };
-    Ok(output.into())
+    output.extend(crate::util::explain_impl(
+        &ident,
+        &generics,
+        &transformed_sql,
+        &parameter_list,
+        &requires,
+        timeout,
+        tag,
+        redact,
+    ));
+    output.extend(crate::util::query_meta_impl(
+        &ident,
+        &generics,
+        &struct_ast,
+        &args.sql,
+        &transformed_sql,
+        Some(&row_type),
+    ));
+    output.extend(crate::util::validate_impl(&ident, &generics, &struct_ast, Some(&row_type)));
+    output.extend(crate::util::debug_sql_impl(
+        &ident,
+        &generics,
+        args.sql,
+        named_parameters,
+        &redacted,
+        &parameter_list,
+    ));
+
+    if let Some(pagination) = pagination {
+        let cursor_type = pagination.cursor_type();
+        let page_sql = pagination.page_sql(nested_sql, struct_ast.fields.len());
+        let query_page_body = trace(
+            "query_page",
+            &transformed_sql,
+            quote!(|page: &postgres_named_parameters::Page<Self::Row, Self::Cursor>| page.rows.len() as u64),
+            wrap_body(
+                &transformed_sql,
+                pagination.query_page_body(nested_sql, struct_ast.fields.len(), &parameter_list, tag),
+            ),
+        );
+        output.extend(quote! {
+            #[automatically_derived]
+            impl #generics postgres_named_parameters::PaginatedQuery for #ident #generics #where_clause {
+                type Cursor = #cursor_type;
+                const PAGE_FINGERPRINTS: &'static [u64] = &[#(postgres_named_parameters::sql_fingerprint(#page_sql)),*];
+                fn query_page(
+                    &self,
+                    connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+                    cursor: Option<&Self::Cursor>,
+                    limit: i64,
+                ) -> Result<postgres_named_parameters::Page<Self::Row, Self::Cursor>, postgres_named_parameters::Error> {
+                    #query_page_body
+                }
+            }
+        });
+    }
+    Ok(output)
 }
@@ -0,0 +1,75 @@
+use attribute_derive::FromAttr;
+use quote::quote;
+use syn::{DeriveInput, Type};
+
+/// The `#[from_row(...)]` attributes of postgres-from-row, which say which
+/// column each field is decoded from.
+#[derive(FromAttr)]
+#[attribute(ident = from_row)]
+struct FromRowFieldHelperAttribute {
+    flatten: bool,
+    #[attribute(example = r#""i64""#)]
+    from: Option<String>,
+    #[attribute(example = r#""i64""#)]
+    try_from: Option<String>,
+    #[attribute(example = r#""first_name""#)]
+    rename: Option<String>,
+}
+
+pub fn derive_row_columns_impl(ast: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let syn::Data::Struct(struct_ast) = ast.data else {
+        return Err(syn::Error::new(
+            ast.ident.span(),
+            "#[derive(RowColumns)] can only be used on structs",
+        ));
+    };
+    let syn::Fields::Named(named_fields) = struct_ast.fields else {
+        return Err(syn::Error::new(
+            ast.ident.span(),
+            "#[derive(RowColumns)] can only be used on structs with named fields",
+        ));
+    };
+
+    let mut generics = ast.generics;
+    let mut predicates = vec![];
+    let mut checks = vec![];
+    for field in &named_fields.named {
+        let field_args = FromRowFieldHelperAttribute::from_attributes(&field.attrs)?;
+        let field_name = field.ident.clone().expect("named fields have identifiers");
+        // Like postgres-from-row, decode into the `from` or `try_from` type if
+        // one is given
+        let ty = match field_args.from.or(field_args.try_from) {
+            Some(ty) => syn::parse_str::<Type>(&ty)?,
+            None => field.ty.clone(),
+        };
+        if field_args.flatten {
+            predicates.push(quote!(#ty: postgres_named_parameters::RowColumns));
+            checks.push(quote! {
+                problems.extend(<#ty as postgres_named_parameters::RowColumns>::check_columns(columns));
+            });
+        } else {
+            let column = field_args.rename.unwrap_or_else(|| field_name.to_string());
+            predicates.push(quote!(#ty: postgres_named_parameters::postgres::types::FromSqlOwned));
+            checks.push(quote! {
+                problems.extend(postgres_named_parameters::internal::row_columns_for_derive_macro::check_named_column::<#ty>(columns, #column));
+            });
+        }
+    }
+    let where_clause = generics.make_where_clause();
+    for predicate in predicates {
+        where_clause.predicates.push(syn::parse2(predicate)?);
+    }
+
+    let ident = ast.ident;
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics postgres_named_parameters::RowColumns for #ident #type_generics #where_clause {
+            fn check_columns(columns: &[postgres_named_parameters::postgres::Column]) -> Vec<String> {
+                let mut problems = Vec::new();
+                #(#checks)*
+                problems
+            }
+        }
+    })
+}
@@ -1,16 +1,28 @@
 use attribute_derive::FromAttr;
 use quote::quote;
-use syn::DeriveInput;
+use syn::{DeriveInput, Expr, Type};
 
 #[derive(FromAttr)]
 #[attribute(ident = statement)]
 struct StatementTraitHelperAttribute {
     #[attribute(example = r#""DELETE FROM Person WHERE id = @id""#)]
     sql: String,
+    #[attribute(example = "crate::my_database_tables::Person")]
+    returning: Option<Type>,
+    #[attribute(example = r#""2s""#)]
+    timeout: Option<String>,
+    #[attribute(example = r#""0..=1""#)]
+    expect_rows: Option<Expr>,
+    requires_transaction: bool,
+    tag: bool,
+    redact: bool,
 }
 
 pub fn derive_statement_impl(ast: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
-    let args = StatementTraitHelperAttribute::from_attributes(&ast.attrs)?;
+    // `on_constraint(...)` isn't `name = value` syntax, so parse it ourselves
+    let (attrs, on_constraint) = crate::util::extract_nested_arguments(&ast.attrs, "statement", "on_constraint");
+    let args = StatementTraitHelperAttribute::from_attributes(&attrs)?;
+    let constraint_mappings = crate::constraint::ConstraintMappings::parse(on_constraint)?;
     let syn::Data::Struct(struct_ast) = ast.data else {
         return Err(syn::Error::new(
             ast.ident.span(),
@@ -18,27 +30,152 @@ pub fn derive_statement_impl(ast: DeriveInput) -> syn::Result<proc_macro2::Token
         ));
     };
 
+    let timeout = crate::util::parse_timeout(args.timeout.as_deref(), &ast.ident)?;
+    let expect_rows = crate::util::parse_expect_rows(args.expect_rows.as_ref())?;
     let named_parameters = crate::util::get_field_names(&struct_ast);
     let parameter_list = crate::util::get_parameter_list(&struct_ast);
-    let transformed_sql = match crate::numberify::numberify(args.sql, named_parameters) {
+    let redacted = crate::util::get_redacted_parameters(&struct_ast, args.redact)?;
+    let redact = !redacted.is_empty();
+    let transformed_sql = match crate::numberify::numberify(args.sql.clone(), named_parameters.clone()) {
         Ok(sql) => sql,
         Err(err) => {
             let err = format!("Error with SQL provided to #[derive(Statement)]: {}", err);
             return Err(syn::Error::new(ast.ident.span(), err));
         }
     };
+    let tag = args.tag || cfg!(feature = "sql-tags");
+    let transformed_sql = if tag {
+        crate::util::tag_sql(&transformed_sql, &ast.ident)
+    } else {
+        transformed_sql
+    };
+
+    let requires = if args.requires_transaction {
+        quote!(postgres_named_parameters::connection::InTransaction)
+    } else {
+        quote!(postgres_named_parameters::connection::AnyConnection)
+    };
 
     let generics = ast.generics;
     let ident = ast.ident;
     let where_clause = &generics.where_clause;
 
-    let output = quote! {
+    let sql = crate::util::sql_expression(&transformed_sql, tag);
+    let wrap_body = |function, rows_affected: proc_macro2::TokenStream, body| {
+        let body = crate::util::wrap_with_tracing(
+            &ident,
+            function,
+            &transformed_sql,
+            "db.response.affected_rows",
+            rows_affected.clone(),
+            crate::util::wrap_with_context(
+                &ident,
+                &transformed_sql,
+                redact,
+                crate::util::wrap_with_expected_rows(
+                    expect_rows,
+                    rows_affected,
+                    crate::util::wrap_with_timeout(timeout, body),
+                ),
+            ),
+        );
+        match &constraint_mappings {
+            Some(constraint_mappings) => constraint_mappings.wrap(body),
+            None => body,
+        }
+    };
+    let error_type = match &constraint_mappings {
+        Some(constraint_mappings) => constraint_mappings.statement_error_type(),
+        None => quote!(postgres_named_parameters::Error),
+    };
+    let execute_statement_body = wrap_body(
+        "execute_statement",
+        quote!(|rows_affected: &u64| *rows_affected),
+        quote! {
+            Ok(connection.execute(#sql, #parameter_list)?)
+        },
+    );
+
+    let mut output = quote! {
         #[automatically_derived]
         impl #generics postgres_named_parameters::Statement for #ident #generics #where_clause {
-            fn execute_statement(&self, connection: &mut impl postgres_named_parameters::postgres::GenericClient) -> Result<u64, postgres_named_parameters::postgres::error::Error> {
-                connection.execute(#transformed_sql, #parameter_list)
+            type Requires = #requires;
+            type Error = #error_type;
+            const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(#transformed_sql);
+            fn execute_statement(
+                &self,
+                connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+            ) -> Result<u64, Self::Error> {
+                #execute_statement_body
             }
         }
     };
-    Ok(output.into())
+    output.extend(crate::util::explain_impl(
+        &ident,
+        &generics,
+        &transformed_sql,
+        &parameter_list,
+        &requires,
+        timeout,
+        tag,
+        redact,
+    ));
+    output.extend(crate::util::query_meta_impl(
+        &ident,
+        &generics,
+        &struct_ast,
+        &args.sql,
+        &transformed_sql,
+        args.returning.as_ref(),
+    ));
+    output.extend(crate::util::validate_impl(&ident, &generics, &struct_ast, args.returning.as_ref()));
+    output.extend(crate::util::debug_sql_impl(
+        &ident,
+        &generics,
+        args.sql,
+        named_parameters,
+        &redacted,
+        &parameter_list,
+    ));
+
+    if let Some(returning_type) = args.returning {
+        let execute_returning_all_body = wrap_body(
+            "execute_returning_all",
+            quote!(
+                |returned: &postgres_named_parameters::Returned<Self::Row>| returned.rows_affected
+            ),
+            quote! {
+                let parameters: &[&(dyn postgres_named_parameters::postgres::types::ToSql + Sync)] = #parameter_list;
+                let row_iter = connection.query_raw(#sql, parameters.iter().copied())?;
+                postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row_iter::<Self::Row, _>(row_iter)
+            },
+        );
+        let execute_returning_one_body = wrap_body(
+            "execute_returning_one",
+            quote!(|_: &Self::Row| 1),
+            quote! {
+                postgres_named_parameters::internal::wrapper_for_derive_macro::execute_returning_one::<Self::Row, _>(connection, #sql, #parameter_list)
+            },
+        );
+        output.extend(quote! {
+            #[automatically_derived]
+            impl #generics postgres_named_parameters::ReturningStatement for #ident #generics #where_clause {
+                type Row = #returning_type;
+                fn execute_returning_all(
+                    &self,
+                    connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+                ) -> Result<postgres_named_parameters::Returned<Self::Row>, Self::Error> {
+                    #execute_returning_all_body
+                }
+
+                fn execute_returning_one(
+                    &self,
+                    connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+                ) -> Result<Self::Row, Self::Error> {
+                    #execute_returning_one_body
+                }
+            }
+        });
+    }
+    Ok(output)
 }
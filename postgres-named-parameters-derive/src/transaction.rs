@@ -0,0 +1,199 @@
+use attribute_derive::FromAttr;
+use quote::{format_ident, quote};
+use syn::visit_mut::VisitMut;
+use syn::{DeriveInput, Type};
+
+#[derive(FromAttr)]
+#[attribute(ident = transaction)]
+struct TransactionHelperAttribute {
+    error: Option<Type>,
+}
+
+#[derive(FromAttr)]
+#[attribute(ident = transaction)]
+struct TransactionFieldHelperAttribute {
+    execute_statement: bool,
+    execute_returning_all: bool,
+    execute_returning_one: bool,
+    query_all: bool,
+    query_opt: bool,
+    query_one: bool,
+    query_exists: bool,
+    query_count: bool,
+}
+
+impl TransactionFieldHelperAttribute {
+    /// Return the name of the method to run the field with, defaulting to
+    /// `execute_statement`.
+    fn method(&self, field: &syn::Field) -> syn::Result<&'static str> {
+        let methods = [
+            (self.execute_statement, "execute_statement"),
+            (self.execute_returning_all, "execute_returning_all"),
+            (self.execute_returning_one, "execute_returning_one"),
+            (self.query_all, "query_all"),
+            (self.query_opt, "query_opt"),
+            (self.query_one, "query_one"),
+            (self.query_exists, "query_exists"),
+            (self.query_count, "query_count"),
+        ];
+        let mut selected = methods.iter().filter(|(is_selected, _)| *is_selected);
+        match (selected.next(), selected.next()) {
+            (None, _) => Ok("execute_statement"),
+            (Some((_, method)), None) => Ok(method),
+            (Some(_), Some(_)) => Err(syn::Error::new_spanned(
+                field,
+                "Only one method can be specified in #[transaction(...)]",
+            )),
+        }
+    }
+}
+
+pub fn derive_transaction_impl(ast: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let syn::Data::Struct(struct_ast) = ast.data else {
+        return Err(syn::Error::new(
+            ast.ident.span(),
+            "#[derive(Transaction)] can only be used on structs",
+        ));
+    };
+    if let Some(type_parameter) = ast.generics.type_params().next() {
+        return Err(syn::Error::new(
+            type_parameter.ident.span(),
+            "#[derive(Transaction)] does not support type parameters (only lifetimes)",
+        ));
+    }
+
+    let attributes = TransactionHelperAttribute::from_attributes(&ast.attrs)?;
+    let error_type = match attributes.error {
+        Some(error_type) => quote!(#error_type),
+        None => quote!(postgres_named_parameters::Error),
+    };
+
+    let ident = ast.ident;
+    let output_ident = format_ident!("{}Output", ident);
+    let vis = ast.vis;
+
+    let mut output_fields = vec![];
+    let mut steps = vec![];
+    let mut field_accessors = vec![];
+    for (index, field) in struct_ast.fields.iter().enumerate() {
+        let method = TransactionFieldHelperAttribute::from_attributes(&field.attrs)?.method(field)?;
+        let method_ident = format_ident!("{}", method);
+        let output_type = output_type(method, &field.ty);
+        let accessor = match &field.ident {
+            Some(field_ident) => quote!(#field_ident),
+            None => {
+                let index = syn::Index::from(index);
+                quote!(#index)
+            }
+        };
+        let doc = format!("The result of running `{}` using `{}`.", accessor, method);
+        let output_field = match &field.ident {
+            Some(field_ident) => quote!(#[doc = #doc] pub #field_ident: #output_type),
+            None => quote!(#[doc = #doc] pub #output_type),
+        };
+        output_fields.push(output_field);
+        let step = format_ident!("step_{}", index);
+        steps.push(quote! {
+            let #step = self.#accessor.#method_ident(&mut transaction)?;
+        });
+        field_accessors.push((accessor, step));
+    }
+
+    let struct_doc = format!("The results of running each step of [{}] as a transaction.", ident);
+    let (output_struct, construct_output) = match struct_ast.fields {
+        syn::Fields::Named(_) => {
+            let fields = field_accessors
+                .iter()
+                .map(|(accessor, step)| quote!(#accessor: #step));
+            (
+                quote! {
+                    #[doc = #struct_doc]
+                    #vis struct #output_ident {
+                        #(#output_fields),*
+                    }
+                },
+                quote!(#output_ident { #(#fields),* }),
+            )
+        }
+        syn::Fields::Unnamed(_) => {
+            let fields = field_accessors.iter().map(|(_, step)| step);
+            (
+                quote! {
+                    #[doc = #struct_doc]
+                    #vis struct #output_ident(#(#output_fields),*);
+                },
+                quote!(#output_ident(#(#fields),*)),
+            )
+        }
+        syn::Fields::Unit => (
+            quote! {
+                #[doc = #struct_doc]
+                #vis struct #output_ident;
+            },
+            quote!(#output_ident),
+        ),
+    };
+
+    let generics = ast.generics;
+    let where_clause = &generics.where_clause;
+
+    let output = quote! {
+        #output_struct
+
+        #[automatically_derived]
+        impl #generics postgres_named_parameters::Transaction for #ident #generics #where_clause {
+            type Output = #output_ident;
+            type Error = #error_type;
+
+            fn execute_transaction(
+                &self,
+                connection: &mut impl postgres_named_parameters::postgres::GenericClient,
+            ) -> Result<Self::Output, Self::Error> {
+                use postgres_named_parameters::{Query as _, ReturningStatement as _, Statement as _};
+                let mut transaction = connection
+                    .transaction()
+                    .map_err(postgres_named_parameters::Error::from)?;
+                #(#steps)*
+                transaction
+                    .commit()
+                    .map_err(postgres_named_parameters::Error::from)?;
+                Ok(#construct_output)
+            }
+        }
+    };
+    Ok(output)
+}
+
+/// The type returned by running a field of the given type using the given
+/// method.
+fn output_type(method: &str, field_type: &Type) -> proc_macro2::TokenStream {
+    // The output struct has no generics, so refer to the field's type with any
+    // lifetimes replaced by 'static. Rows are owned, so they don't depend on
+    // the lifetimes anyway.
+    let mut field_type = field_type.clone();
+    while let Type::Reference(reference) = field_type {
+        field_type = *reference.elem;
+    }
+    StaticLifetimes.visit_type_mut(&mut field_type);
+
+    let query_row = quote!(<#field_type as postgres_named_parameters::Query>::Row);
+    let returning_row = quote!(<#field_type as postgres_named_parameters::ReturningStatement>::Row);
+    match method {
+        "execute_returning_all" => quote!(postgres_named_parameters::Returned<#returning_row>),
+        "execute_returning_one" => returning_row,
+        "query_all" => quote!(Vec<#query_row>),
+        "query_opt" => quote!(Option<#query_row>),
+        "query_one" => query_row,
+        "query_exists" => quote!(bool),
+        "query_count" => quote!(i64),
+        _ => quote!(u64),
+    }
+}
+
+struct StaticLifetimes;
+
+impl VisitMut for StaticLifetimes {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+        *lifetime = syn::Lifetime::new("'static", lifetime.span());
+    }
+}
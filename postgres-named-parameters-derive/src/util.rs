@@ -1,4 +1,11 @@
-use quote::quote;
+use attribute_derive::{FlagOrValue, FromAttr};
+use quote::{quote, quote_spanned, ToTokens};
+
+#[derive(FromAttr)]
+#[attribute(ident = param)]
+struct ParamFieldHelperAttribute {
+    redact: FlagOrValue<bool>,
+}
 
 pub fn get_field_names(struct_ast: &syn::DataStruct) -> Vec<String> {
     match struct_ast.fields {
@@ -33,10 +40,741 @@ pub fn get_parameter_list(struct_ast: &syn::DataStruct) -> proc_macro2::TokenStr
     }
 }
 
+/// Get the indices of the parameters which are redacted, either using
+/// `#[param(redact)]` or because the whole struct is redacted (in which case
+/// `#[param(redact = false)]` opts a parameter out).
+pub fn get_redacted_parameters(
+    struct_ast: &syn::DataStruct,
+    redact_by_default: bool,
+) -> syn::Result<Vec<usize>> {
+    let mut redacted = vec![];
+    for (index, field) in struct_ast.fields.iter().enumerate() {
+        let args = ParamFieldHelperAttribute::from_attributes(&field.attrs)?;
+        let redact = match args.redact {
+            FlagOrValue::None => redact_by_default,
+            FlagOrValue::Flag => true,
+            FlagOrValue::Value(redact) => redact,
+        };
+        if redact {
+            redacted.push(index);
+        }
+    }
+    Ok(redacted)
+}
+
 fn get_field_identifiers(named_fields: &syn::FieldsNamed) -> Vec<proc_macro2::Ident> {
     named_fields
         .named
         .iter()
         .filter_map(|named_field| named_field.ident.as_ref().map(|i| i.to_owned()))
         .collect()
-}
\ No newline at end of file
+}
+/// Remove any trailing semicolons (and whitespace) from the given SQL so that
+/// it can be nested inside another query.
+pub fn trim_sql_terminator(sql: &str) -> &str {
+    sql.trim_end_matches(|ch: char| ch == ';' || ch.is_whitespace())
+}
+
+/// Append a comment saying which struct the SQL belongs to and where it's
+/// defined, e.g. `/* query='GetPeople',file='src/db.rs',line=42 */`, so that
+/// it can be recognised in `pg_stat_activity` and the Postgres logs. The
+/// comment goes on its own line in case the SQL ends with a `--` comment.
+pub fn tag_sql(sql: &str, ident: &proc_macro2::Ident) -> String {
+    let span = ident.span().unwrap();
+    format!(
+        "{}\n/* query='{}',file='{}',line={} */",
+        trim_sql_terminator(sql),
+        ident,
+        escape_tag_value(&span.file()),
+        span.line(),
+    )
+}
+
+/// Percent-encode the characters of a tag value which could end the quoted
+/// value or start or end a comment (Postgres comments nest, so an unmatched
+/// `/*` would swallow the rest of the SQL). This matches the escaping of
+/// `postgres_named_parameters::set_sql_tag_hook`.
+fn escape_tag_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '%' => escaped.push_str("%25"),
+            '\'' => escaped.push_str("%27"),
+            '*' => escaped.push_str("%2A"),
+            '\\' => escaped.push_str("%5C"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// The expression to pass the given SQL to the connection with. If the SQL is
+/// tagged, this adds any tags from the runtime hook.
+pub fn sql_expression(sql: &str, tag: bool) -> proc_macro2::TokenStream {
+    if tag {
+        quote!(&*postgres_named_parameters::internal::sql_tag_for_derive_macro::with_runtime_tags(#sql))
+    } else {
+        quote!(#sql)
+    }
+}
+
+/// Parse the `timeout` parameter (e.g. `"2s"`) into a number of milliseconds.
+/// The units are the same as the ones Postgres accepts for `statement_timeout`.
+pub fn parse_timeout(
+    timeout: Option<&str>,
+    ident: &proc_macro2::Ident,
+) -> syn::Result<Option<u64>> {
+    let Some(timeout) = timeout else {
+        return Ok(None);
+    };
+    let timeout = timeout.trim();
+    let unit_start = timeout
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(timeout.len());
+    let (amount, unit) = timeout.split_at(unit_start);
+    let milliseconds_per_unit = match unit.trim() {
+        "ms" => Some(1),
+        "s" => Some(1000),
+        "min" => Some(60 * 1000),
+        "h" => Some(60 * 60 * 1000),
+        _ => None,
+    };
+    let milliseconds = match (amount.parse::<u64>(), milliseconds_per_unit) {
+        (Ok(amount), Some(milliseconds_per_unit)) if amount > 0 => amount.checked_mul(milliseconds_per_unit),
+        _ => return Err(syn::Error::new(
+            ident.span(),
+            format!(
+                r#"Invalid timeout "{}": expected a positive whole number followed by one of the units "ms", "s", "min" or "h" (e.g. "2s")"#,
+                timeout
+            ),
+        )),
+    };
+    // statement_timeout is an integer number of milliseconds
+    match milliseconds {
+        Some(milliseconds) if milliseconds <= i32::MAX as u64 => Ok(Some(milliseconds)),
+        _ => Err(syn::Error::new(
+            ident.span(),
+            format!(
+                r#"Invalid timeout "{}": Postgres doesn't support timeouts longer than {}ms (about 24 days)"#,
+                timeout,
+                i32::MAX
+            ),
+        )),
+    }
+}
+
+/// Parse the `expect_rows` parameter (e.g. `1`, `"0..=1"` or `"at_least(1)"`)
+/// into the minimum and (optional) maximum number of rows.
+pub fn parse_expect_rows(
+    expect_rows: Option<&syn::Expr>,
+) -> syn::Result<Option<(u64, Option<u64>)>> {
+    use syn::spanned::Spanned;
+
+    let Some(expect_rows) = expect_rows else {
+        return Ok(None);
+    };
+    let error = || {
+        syn::Error::new(
+            expect_rows.span(),
+            r#"Expected `expect_rows` to be a number of rows (e.g. `1`) or a string containing a range (e.g. `"0..=1"` or `"1.."`), `"at_least(N)"` or `"at_most(N)"`"#,
+        )
+    };
+    let range = match expect_rows {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(rows),
+            ..
+        }) => {
+            let rows = rows.base10_parse::<u64>()?;
+            (rows, Some(rows))
+        }
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(range),
+            ..
+        }) => {
+            let range = range.value().replace(char::is_whitespace, "");
+            let parse = |rows: &str| rows.parse::<u64>().map_err(|_| error());
+            if let Some(rows) = range
+                .strip_prefix("at_least(")
+                .and_then(|rest| rest.strip_suffix(')'))
+            {
+                (parse(rows)?, None)
+            } else if let Some(rows) = range
+                .strip_prefix("at_most(")
+                .and_then(|rest| rest.strip_suffix(')'))
+            {
+                (0, Some(parse(rows)?))
+            } else if let Some((min, max)) = range.split_once("..=") {
+                let min = if min.is_empty() { 0 } else { parse(min)? };
+                (min, Some(parse(max)?))
+            } else if let Some((min, max)) = range.split_once("..") {
+                let min = if min.is_empty() { 0 } else { parse(min)? };
+                let max = if max.is_empty() {
+                    None
+                } else {
+                    Some(parse(max)?.checked_sub(1).ok_or_else(error)?)
+                };
+                (min, max)
+            } else {
+                let rows = parse(&range)?;
+                (rows, Some(rows))
+            }
+        }
+        _ => return Err(error()),
+    };
+    match range {
+        (min, Some(max)) if max < min => Err(syn::Error::new(
+            expect_rows.span(),
+            "The `expect_rows` range is empty, so the statement would always fail",
+        )),
+        range => Ok(Some(range)),
+    }
+}
+
+/// Wrap the body of a generated method so that it fails (and is rolled back)
+/// if it affects a different number of rows than expected. `rows_affected`
+/// takes the method's result and returns the number of rows it affected.
+pub fn wrap_with_expected_rows(
+    expect_rows: Option<(u64, Option<u64>)>,
+    rows_affected: proc_macro2::TokenStream,
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let Some((min, max)) = expect_rows else {
+        return body;
+    };
+    let max = match max {
+        Some(max) => quote!(Some(#max)),
+        None => quote!(None),
+    };
+    quote! {
+        postgres_named_parameters::internal::expect_rows_for_derive_macro::with_expected_rows(
+            connection,
+            postgres_named_parameters::ExpectedRows { min: #min, max: #max },
+            #rows_affected,
+            |connection| {
+                #body
+            },
+        )
+    }
+}
+
+/// Generate the `Explain` implementation shared by `#[derive(Query)]` and
+/// `#[derive(Statement)]`, which runs `EXPLAIN` on the given SQL.
+#[allow(clippy::too_many_arguments)]
+pub fn explain_impl(
+    ident: &proc_macro2::Ident,
+    generics: &syn::Generics,
+    sql: &str,
+    parameter_list: &proc_macro2::TokenStream,
+    requires: &proc_macro2::TokenStream,
+    timeout: Option<u64>,
+    tag: bool,
+    redact: bool,
+) -> proc_macro2::TokenStream {
+    let where_clause = &generics.where_clause;
+    let sql_expression = sql_expression(sql, tag);
+    let body = wrap_with_tracing(
+        ident,
+        "explain",
+        sql,
+        "db.response.returned_rows",
+        quote!(|_: &postgres_named_parameters::Plan| 1),
+        wrap_with_context(
+            ident,
+            sql,
+            redact,
+            wrap_with_timeout(
+                timeout,
+                quote! {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(connection, #sql_expression, #parameter_list, options)
+                },
+            ),
+        ),
+    );
+    quote! {
+        #[automatically_derived]
+        impl #generics postgres_named_parameters::Explain for #ident #generics #where_clause {
+            type Requires = #requires;
+
+            fn explain(
+                &self,
+                connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+                options: postgres_named_parameters::ExplainOptions,
+            ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+                #body
+            }
+        }
+    }
+}
+
+/// Generate the `QueryMeta` implementation shared by `#[derive(Query)]` and
+/// `#[derive(Statement)]`.
+pub fn query_meta_impl(
+    ident: &proc_macro2::Ident,
+    generics: &syn::Generics,
+    struct_ast: &syn::DataStruct,
+    original_sql: &str,
+    numbered_sql: &str,
+    row_type: Option<&syn::Type>,
+) -> proc_macro2::TokenStream {
+    let where_clause = &generics.where_clause;
+    let name = ident.to_string();
+    let params = struct_ast.fields.iter().enumerate().map(|(index, field)| {
+        let name = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => index.to_string(),
+        };
+        let ty = type_name(&field.ty);
+        quote!((#name, #ty))
+    });
+    let row_type = match row_type {
+        Some(row_type) => {
+            let row_type = type_name(row_type);
+            quote!(Some(#row_type))
+        }
+        None => quote!(None),
+    };
+    // `Location::caller()` gives the location of the call itself when not
+    // inside a `#[track_caller]` function, so give it the span of the struct's
+    // name. Unlike `file!()`, this keeps absolute paths out of the expand tests.
+    let location = quote_spanned!(ident.span()=> std::panic::Location::caller());
+    quote! {
+        #[automatically_derived]
+        impl #generics postgres_named_parameters::QueryMeta for #ident #generics #where_clause {
+            const NAME: &'static str = #name;
+            const ORIGINAL_SQL: &'static str = #original_sql;
+            const NUMBERED_SQL: &'static str = #numbered_sql;
+            const PARAMS: &'static [(&'static str, &'static str)] = &[#(#params),*];
+            const ROW_TYPE: Option<&'static str> = #row_type;
+            const LOCATION: &'static std::panic::Location<'static> = #location;
+        }
+    }
+}
+
+/// Generate the `Validate` implementation shared by `#[derive(Query)]` and
+/// `#[derive(Statement)]`, which `validate_all` uses to check the struct
+/// against the database, and register the struct so that `validate_all` finds
+/// it. Structs with type parameters can't be registered, since there's no
+/// single type to register.
+pub fn validate_impl(
+    ident: &proc_macro2::Ident,
+    generics: &syn::Generics,
+    struct_ast: &syn::DataStruct,
+    row_type: Option<&syn::Type>,
+) -> proc_macro2::TokenStream {
+    let where_clause = &generics.where_clause;
+    let parameters = struct_ast.fields.iter().enumerate().map(|(index, field)| {
+        let name = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => index.to_string(),
+        };
+        let ty = &field.ty;
+        let type_name = type_name(ty);
+        quote!((#name, #type_name, <#ty as postgres_named_parameters::postgres::types::ToSql>::accepts))
+    });
+    let check_columns = match row_type {
+        Some(row_type) => quote! {
+            use postgres_named_parameters::internal::registry_for_derive_macro::{CheckDecodeRow as _, CheckRowColumns as _};
+            (&&postgres_named_parameters::internal::registry_for_derive_macro::ColumnChecker::<#row_type>::new()).check_columns(columns)
+        },
+        None => quote! {
+            let _ = columns;
+            Vec::new()
+        },
+    };
+    let mut output = quote! {
+        #[automatically_derived]
+        impl #generics postgres_named_parameters::internal::registry_for_derive_macro::Validate for #ident #generics #where_clause {
+            fn check_parameters(types: &[postgres_named_parameters::postgres::types::Type]) -> Vec<String> {
+                postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+                    types,
+                    &[#(#parameters),*],
+                )
+            }
+            fn check_columns(columns: &[postgres_named_parameters::postgres::Column]) -> Vec<String> {
+                #check_columns
+            }
+        }
+    };
+    let only_lifetimes = generics
+        .params
+        .iter()
+        .all(|param| matches!(param, syn::GenericParam::Lifetime(_)));
+    if only_lifetimes {
+        let ty = if generics.params.is_empty() {
+            quote!(#ident)
+        } else {
+            let lifetimes = generics.params.iter().map(|_| quote!('static));
+            quote!(#ident<#(#lifetimes),*>)
+        };
+        output.extend(quote! {
+            postgres_named_parameters::register_for_derive_macro!(#ty);
+        });
+    }
+    output
+}
+
+/// Render a type as it would be written by hand, e.g. `Option<&'a str>`
+/// rather than the `Option < & 'a str >` given by its tokens.
+fn type_name(ty: &syn::Type) -> String {
+    ty.to_token_stream()
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace(" ;", ";")
+        .replace("& ", "&")
+}
+
+/// Generate the `DebugSql` implementation shared by `#[derive(Query)]` and
+/// `#[derive(Statement)]`, which inlines the parameters into the given SQL.
+pub fn debug_sql_impl(
+    ident: &proc_macro2::Ident,
+    generics: &syn::Generics,
+    sql: String,
+    named_parameters: Vec<String>,
+    redacted: &[usize],
+    parameter_list: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let where_clause = &generics.where_clause;
+    let (fragments, indices) = crate::numberify::split_parameters(sql, named_parameters)
+        .expect("the SQL was already checked by numberify");
+    quote! {
+        #[automatically_derived]
+        impl #generics postgres_named_parameters::DebugSql for #ident #generics #where_clause {
+            fn to_debug_sql(&self) -> String {
+                postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+                    &[#(#fragments),*],
+                    &[#(#indices),*],
+                    &[#(#redacted),*],
+                    #parameter_list,
+                )
+            }
+        }
+    }
+}
+
+/// Wrap the body of a generated method so that the current `QueryObserver` is
+/// told about it, inside a `tracing` span named after the statement or query
+/// (if the runtime crate's `tracing` feature is enabled). `rows` takes the
+/// method's result and returns the number of rows returned or affected, which
+/// is recorded in the span field `rows_field`. This should wrap
+/// [wrap_with_context] so that the error it sees records the SQLSTATE.
+pub fn wrap_with_tracing(
+    ident: &proc_macro2::Ident,
+    function: &str,
+    sql: &str,
+    rows_field: &str,
+    rows: proc_macro2::TokenStream,
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let name = ident.to_string();
+    // The operation is the first keyword of the SQL, e.g. SELECT or INSERT
+    let operation = sql
+        .split(|ch: char| !ch.is_alphabetic())
+        .find(|word| !word.is_empty())
+        .unwrap_or_default()
+        .to_uppercase();
+    quote! {
+        postgres_named_parameters::instrument_for_derive_macro!(
+            #name,
+            #operation,
+            #function,
+            #sql,
+            #rows_field,
+            #rows,
+            #body
+        )
+    }
+}
+
+/// Wrap the body of a generated method so that any error it returns records
+/// the name of the statement or query and the SQL that was run. This should be
+/// the outermost wrapper. If `redact` is set (i.e. some parameters are
+/// redacted), the details of database errors are hidden.
+pub fn wrap_with_context(
+    ident: &proc_macro2::Ident,
+    sql: &str,
+    redact: bool,
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let name = ident.to_string();
+    let run = if redact {
+        quote!(run_redacted)
+    } else {
+        quote!(run)
+    };
+    quote! {
+        postgres_named_parameters::internal::error_for_derive_macro::#run(#name, #sql, || {
+            #body
+        })
+    }
+}
+
+/// Wrap the body of a generated method so that it runs with the given
+/// statement timeout (if any).
+pub fn wrap_with_timeout(
+    timeout: Option<u64>,
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match timeout {
+        None => body,
+        Some(timeout) => quote! {
+            postgres_named_parameters::internal::timeout_for_derive_macro::with_statement_timeout(
+                connection,
+                #timeout,
+                |connection| {
+                    #body
+                },
+            )
+        },
+    }
+}
+
+/// Wrap the body of a generated method so that it runs in a read-only
+/// transaction, if `read_only` is set. This should be the outermost wrapper
+/// (apart from [wrap_with_context]) so that it can tell whether it was given a
+/// client or a transaction.
+pub fn wrap_with_read_only(read_only: bool, body: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    if read_only {
+        quote! {
+            postgres_named_parameters::internal::read_only_for_derive_macro::with_read_only::<_, Self::Requires, _, _>(
+                connection,
+                |connection| {
+                    #body
+                },
+            )
+        }
+    } else {
+        body
+    }
+}
+
+/// Remove any `name(...)` arguments from the helper attributes with the given
+/// ident (e.g. `on_constraint(...)` from `#[statement(...)]`), returning the
+/// remaining attributes and the contents of each removed argument. This is for
+/// arguments whose syntax attribute-derive can't parse.
+pub fn extract_nested_arguments(
+    attrs: &[syn::Attribute],
+    attribute_ident: &str,
+    argument_ident: &str,
+) -> (Vec<syn::Attribute>, Vec<proc_macro2::TokenStream>) {
+    use proc_macro2::TokenTree;
+
+    let mut remaining_attrs = vec![];
+    let mut extracted = vec![];
+    for attr in attrs {
+        let syn::Meta::List(list) = &attr.meta else {
+            remaining_attrs.push(attr.clone());
+            continue;
+        };
+        if !attr.path().is_ident(attribute_ident) {
+            remaining_attrs.push(attr.clone());
+            continue;
+        }
+
+        // Split the arguments on top-level commas (commas inside parentheses
+        // are part of a single group token)
+        let mut arguments: Vec<Vec<TokenTree>> = vec![vec![]];
+        for token in list.tokens.clone() {
+            match &token {
+                TokenTree::Punct(punct) if punct.as_char() == ',' => arguments.push(vec![]),
+                _ => arguments.last_mut().expect("there is always an argument").push(token),
+            }
+        }
+
+        let mut remaining_tokens = proc_macro2::TokenStream::new();
+        for argument in arguments {
+            match argument.as_slice() {
+                [TokenTree::Ident(ident), TokenTree::Group(group)]
+                    if ident == argument_ident
+                        && group.delimiter() == proc_macro2::Delimiter::Parenthesis =>
+                {
+                    extracted.push(group.stream());
+                }
+                [] => {}
+                _ => {
+                    if !remaining_tokens.is_empty() {
+                        remaining_tokens.extend(quote!(,));
+                    }
+                    remaining_tokens.extend(argument);
+                }
+            }
+        }
+        let mut attr = attr.clone();
+        if let syn::Meta::List(list) = &mut attr.meta {
+            list.tokens = remaining_tokens;
+        }
+        remaining_attrs.push(attr);
+    }
+    (remaining_attrs, extracted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timeout(timeout: &str) -> syn::Result<Option<u64>> {
+        let ident = proc_macro2::Ident::new("GetPeople", proc_macro2::Span::call_site());
+        parse_timeout(Some(timeout), &ident)
+    }
+
+    #[test]
+    fn parse_timeout_supports_each_unit() {
+        assert_eq!(timeout("250ms").unwrap(), Some(250));
+        assert_eq!(timeout("2s").unwrap(), Some(2_000));
+        assert_eq!(timeout(" 3 min ").unwrap(), Some(180_000));
+        assert_eq!(timeout("1h").unwrap(), Some(3_600_000));
+        let ident = proc_macro2::Ident::new("GetPeople", proc_macro2::Span::call_site());
+        assert_eq!(parse_timeout(None, &ident).unwrap(), None);
+    }
+
+    #[test]
+    fn parse_timeout_rejects_zero_and_garbage() {
+        for garbage in ["0s", "0ms", "2", "s", "", "2 days", "-1s", "1.5s", "2sec"] {
+            let err = timeout(garbage).expect_err(garbage);
+            assert!(err.to_string().contains("positive whole number"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn parse_timeout_rejects_overflow() {
+        assert_eq!(timeout("2147483647ms").unwrap(), Some(i32::MAX as u64));
+        assert_eq!(timeout("596h").unwrap(), Some(596 * 3_600_000));
+        for too_long in ["2147483648ms", "597h", "18446744073709551615h", "99999999999999999999s"] {
+            assert!(timeout(too_long).is_err(), "{}", too_long);
+        }
+    }
+
+    fn expect_rows(expect_rows: &str) -> syn::Result<Option<(u64, Option<u64>)>> {
+        parse_expect_rows(Some(&syn::parse_str(expect_rows).unwrap()))
+    }
+
+    #[test]
+    fn parse_expect_rows_supports_numbers_and_ranges() {
+        assert_eq!(expect_rows("1").unwrap(), Some((1, Some(1))));
+        assert_eq!(expect_rows(r#""1""#).unwrap(), Some((1, Some(1))));
+        assert_eq!(expect_rows(r#""0..=1""#).unwrap(), Some((0, Some(1))));
+        assert_eq!(expect_rows(r#""..=3""#).unwrap(), Some((0, Some(3))));
+        assert_eq!(expect_rows(r#""2..5""#).unwrap(), Some((2, Some(4))));
+        assert_eq!(expect_rows(r#""1..""#).unwrap(), Some((1, None)));
+        assert_eq!(expect_rows(r#"" 1 .. ""#).unwrap(), Some((1, None)));
+        assert_eq!(expect_rows(r#""at_least(1)""#).unwrap(), Some((1, None)));
+        assert_eq!(expect_rows(r#""at_most(2)""#).unwrap(), Some((0, Some(2))));
+        assert_eq!(parse_expect_rows(None).unwrap(), None);
+    }
+
+    #[test]
+    fn parse_expect_rows_rejects_malformed_input() {
+        let malformed_inputs = [
+            "-1",
+            "1.5",
+            "true",
+            r#""""#,
+            r#""one""#,
+            r#""1..=""#,
+            r#""1...3""#,
+            r#""at_least()""#,
+            r#""at_least(1""#,
+        ];
+        for malformed in malformed_inputs {
+            let err = expect_rows(malformed).expect_err(malformed);
+            assert!(err.to_string().contains("Expected `expect_rows`"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn parse_expect_rows_rejects_empty_ranges() {
+        for empty in [r#""2..=1""#, r#""1..1""#, r#""3..2""#] {
+            let err = expect_rows(empty).expect_err(empty);
+            assert!(err.to_string().contains("range is empty"), "{}", err);
+        }
+        assert!(expect_rows(r#""..0""#).is_err());
+    }
+
+    fn extract(attr: syn::Attribute) -> (String, Vec<String>) {
+        let (remaining, extracted) = extract_nested_arguments(&[attr], "statement", "on_constraint");
+        let [remaining] = remaining.as_slice() else {
+            panic!("expected exactly one attribute");
+        };
+        (
+            quote!(#remaining).to_string(),
+            extracted.iter().map(ToString::to_string).collect(),
+        )
+    }
+
+    #[test]
+    fn extract_nested_arguments_removes_the_argument() {
+        let (remaining, extracted) = extract(syn::parse_quote! {
+            #[statement(sql = "DELETE FROM Person", on_constraint("a" => E::A, "b" => E::B), expect_rows = 1)]
+        });
+        assert_eq!(remaining, quote!(#[statement(sql = "DELETE FROM Person", expect_rows = 1)]).to_string());
+        assert_eq!(extracted, [quote!("a" => E::A, "b" => E::B).to_string()]);
+    }
+
+    #[test]
+    fn extract_nested_arguments_only_extracts_top_level_arguments() {
+        // An `on_constraint(...)` nested inside another argument is left alone
+        let (remaining, extracted) = extract(syn::parse_quote! {
+            #[statement(other(on_constraint("a" => E::A)), on_constraint("b" => E::B))]
+        });
+        assert_eq!(remaining, quote!(#[statement(other(on_constraint("a" => E::A)))]).to_string());
+        assert_eq!(extracted, [quote!("b" => E::B).to_string()]);
+    }
+
+    #[test]
+    fn extract_nested_arguments_extracts_each_duplicate() {
+        let (remaining, extracted) = extract(syn::parse_quote! {
+            #[statement(on_constraint("a" => E::A), sql = "SELECT 1", on_constraint("b" => E::B))]
+        });
+        assert_eq!(remaining, quote!(#[statement(sql = "SELECT 1")]).to_string());
+        assert_eq!(
+            extracted,
+            [quote!("a" => E::A).to_string(), quote!("b" => E::B).to_string()]
+        );
+    }
+
+    #[test]
+    fn extract_nested_arguments_ignores_names_in_strings_and_comments() {
+        let (remaining, extracted) = extract(syn::parse_quote! {
+            #[statement(
+                sql = "SELECT on_constraint(1), 'on_constraint(2)' -- on_constraint(3)",
+                // on_constraint("a" => E::A)
+                /* on_constraint("b" => E::B) */
+                redact
+            )]
+        });
+        assert_eq!(
+            remaining,
+            quote!(#[statement(sql = "SELECT on_constraint(1), 'on_constraint(2)' -- on_constraint(3)", redact)])
+                .to_string()
+        );
+        assert!(extracted.is_empty());
+    }
+
+    #[test]
+    fn extract_nested_arguments_leaves_other_attributes_alone() {
+        let attrs: Vec<syn::Attribute> = vec![
+            syn::parse_quote!(#[query(on_constraint("a" => E::A))]),
+            syn::parse_quote!(#[doc = "on_constraint"]),
+        ];
+        let (remaining, extracted) = extract_nested_arguments(&attrs, "statement", "on_constraint");
+        assert_eq!(remaining, attrs);
+        assert!(extracted.is_empty());
+    }
+
+    #[test]
+    fn escape_tag_value_leaves_paths_alone() {
+        assert_eq!(escape_tag_value("src/db/people.rs"), "src/db/people.rs");
+        assert_eq!(escape_tag_value(r"C:\src\db.rs"), "C:%5Csrc%5Cdb.rs");
+    }
+
+    #[test]
+    fn escape_tag_value_escapes_quotes_and_comments() {
+        assert_eq!(escape_tag_value("it's"), "it%27s");
+        assert_eq!(escape_tag_value("a/*b*/c"), "a/%2Ab%2A/c");
+        assert_eq!(escape_tag_value("100%"), "100%25");
+    }
+}
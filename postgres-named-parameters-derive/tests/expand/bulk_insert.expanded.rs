@@ -0,0 +1,48 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+#[bulk_insert(table = "Person", on_conflict = "(first_name, last_name) DO NOTHING")]
+struct Person {
+    first_name: String,
+    last_name: String,
+    hobby: Option<String>,
+    #[bulk_insert(column = "is_alive")]
+    alive: bool,
+    #[bulk_insert(sql_type = "TIMESTAMP")]
+    born: std::time::SystemTime,
+}
+#[automatically_derived]
+impl postgres_named_parameters::BulkInsert for Person {
+    fn insert_many(
+        connection: &mut impl postgres_named_parameters::postgres::GenericClient,
+        rows: &[Self],
+    ) -> Result<u64, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "Person",
+                method: "insert_many",
+                sql: "INSERT INTO Person (first_name, last_name, hobby, is_alive, born) SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::BOOL[], $5::TIMESTAMP[]) ON CONFLICT (first_name, last_name) DO NOTHING",
+            },
+            |rows_affected: &u64| *rows_affected,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "Person",
+                "INSERT INTO Person (first_name, last_name, hobby, is_alive, born) SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::BOOL[], $5::TIMESTAMP[]) ON CONFLICT (first_name, last_name) DO NOTHING",
+                || {
+                    Ok(
+                        connection
+                            .execute(
+                                "INSERT INTO Person (first_name, last_name, hobby, is_alive, born) SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::BOOL[], $5::TIMESTAMP[]) ON CONFLICT (first_name, last_name) DO NOTHING",
+                                &[
+                                    &rows.iter().map(|row| &row.first_name).collect::<Vec<_>>(),
+                                    &rows.iter().map(|row| &row.last_name).collect::<Vec<_>>(),
+                                    &rows.iter().map(|row| &row.hobby).collect::<Vec<_>>(),
+                                    &rows.iter().map(|row| &row.alive).collect::<Vec<_>>(),
+                                    &rows.iter().map(|row| &row.born).collect::<Vec<_>>(),
+                                ],
+                            )?,
+                    )
+                },
+            ),
+        )
+    }
+}
@@ -0,0 +1,15 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+
+#[derive(BulkInsert)]
+#[bulk_insert(table = "Person", on_conflict = "(first_name, last_name) DO NOTHING")]
+struct Person {
+    first_name: String,
+    last_name: String,
+    hobby: Option<String>,
+    #[bulk_insert(column = "is_alive")]
+    alive: bool,
+    #[bulk_insert(sql_type = "TIMESTAMP")]
+    born: std::time::SystemTime,
+}
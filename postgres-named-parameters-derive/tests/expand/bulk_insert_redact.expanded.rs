@@ -0,0 +1,44 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+#[bulk_insert(table = "Account")]
+struct Account {
+    username: String,
+    #[bulk_insert(redact)]
+    password_hash: String,
+}
+#[automatically_derived]
+impl postgres_named_parameters::BulkInsert for Account {
+    fn insert_many(
+        connection: &mut impl postgres_named_parameters::postgres::GenericClient,
+        rows: &[Self],
+    ) -> Result<u64, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "Account",
+                method: "insert_many",
+                sql: "INSERT INTO Account (username, password_hash) SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[])",
+            },
+            |rows_affected: &u64| *rows_affected,
+            || postgres_named_parameters::internal::error_for_derive_macro::run_redacted(
+                "Account",
+                "INSERT INTO Account (username, password_hash) SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[])",
+                || {
+                    Ok(
+                        connection
+                            .execute(
+                                "INSERT INTO Account (username, password_hash) SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[])",
+                                &[
+                                    &rows.iter().map(|row| &row.username).collect::<Vec<_>>(),
+                                    &rows
+                                        .iter()
+                                        .map(|row| &row.password_hash)
+                                        .collect::<Vec<_>>(),
+                                ],
+                            )?,
+                    )
+                },
+            ),
+        )
+    }
+}
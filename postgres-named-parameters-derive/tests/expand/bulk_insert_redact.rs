@@ -0,0 +1,11 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+
+#[derive(BulkInsert)]
+#[bulk_insert(table = "Account")]
+struct Account {
+    username: String,
+    #[bulk_insert(redact)]
+    password_hash: String,
+}
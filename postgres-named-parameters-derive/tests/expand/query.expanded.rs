@@ -9,6 +9,53 @@ struct Person {
     age: i32,
     alive: bool,
 }
+impl postgres_from_row::FromRow for Person
+where
+    String: for<'a> postgres_from_row::tokio_postgres::types::FromSql<'a>,
+    String: for<'a> postgres_from_row::tokio_postgres::types::FromSql<'a>,
+    i32: for<'a> postgres_from_row::tokio_postgres::types::FromSql<'a>,
+    bool: for<'a> postgres_from_row::tokio_postgres::types::FromSql<'a>,
+{
+    fn from_row(row: &postgres_from_row::tokio_postgres::Row) -> Self {
+        Self {
+            first_name: postgres_from_row::tokio_postgres::Row::get::<
+                &str,
+                String,
+            >(row, "first_name"),
+            last_name: postgres_from_row::tokio_postgres::Row::get::<
+                &str,
+                String,
+            >(row, "last_name"),
+            age: postgres_from_row::tokio_postgres::Row::get::<&str, i32>(row, "age"),
+            alive: postgres_from_row::tokio_postgres::Row::get::<
+                &str,
+                bool,
+            >(row, "alive"),
+        }
+    }
+    fn try_from_row(
+        row: &postgres_from_row::tokio_postgres::Row,
+    ) -> std::result::Result<Self, postgres_from_row::tokio_postgres::Error> {
+        Ok(Self {
+            first_name: postgres_from_row::tokio_postgres::Row::try_get::<
+                &str,
+                String,
+            >(row, "first_name")?,
+            last_name: postgres_from_row::tokio_postgres::Row::try_get::<
+                &str,
+                String,
+            >(row, "last_name")?,
+            age: postgres_from_row::tokio_postgres::Row::try_get::<
+                &str,
+                i32,
+            >(row, "age")?,
+            alive: postgres_from_row::tokio_postgres::Row::try_get::<
+                &str,
+                bool,
+            >(row, "alive")?,
+        })
+    }
+}
 #[query(
     sql = "SELECT * FROM Person WHERE (first_name = @name OR last_name = @name) AND alive = @alive",
     row = Person
@@ -20,51 +67,247 @@ struct GetPeople<'a> {
 #[automatically_derived]
 impl<'a> postgres_named_parameters::Query for GetPeople<'a> {
     type Row = Person;
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    const READ_ONLY: bool = false;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+    );
+    const EXISTS_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT EXISTS (SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1\n)",
+    );
+    const COUNT_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT count(*) FROM (SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1\n) _",
+    );
     fn query_all(
         &self,
-        connection: &mut impl postgres_named_parameters::postgres::GenericClient,
-    ) -> Result<Vec<Self::Row>, postgres_named_parameters::postgres::error::Error> {
-        let rows = connection.query(
-            "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
-            &[&self.alive, &self.name],
-        )?;
-        rows.iter()
-            .map(
-                postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<
-                    Self::Row,
-                >,
-            )
-            .collect()
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Vec<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeople",
+                method: "query_all",
+                sql: "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+            },
+            |rows: &Vec<Self::Row>| rows.len() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeople",
+                "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+                || {
+                    let rows = connection
+                        .query(
+                            "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+                            &[&self.alive, &self.name],
+                        )?;
+                    rows.iter()
+                        .map(
+                            postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<
+                                Self::Row,
+                                _,
+                            >,
+                        )
+                        .collect()
+                },
+            ),
+        )
     }
     fn query_opt(
         &self,
-        connection: &mut impl postgres_named_parameters::postgres::GenericClient,
-    ) -> Result<Option<Self::Row>, postgres_named_parameters::postgres::error::Error> {
-        let maybe_row = connection.query_opt(
-            "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
-            &[&self.alive, &self.name],
-        )?;
-        match maybe_row {
-            None => Ok(None),
-            Some(row) => {
-                let decoded_row =
-                    postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Option<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeople",
+                method: "query_opt",
+                sql: "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+            },
+            |row: &Option<Self::Row>| row.is_some() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeople",
+                "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_opt::<
                         Self::Row,
-                    >(&row)?;
-                Ok(Some(decoded_row))
-            }
-        }
+                        _,
+                    >(
+                        connection,
+                        "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+                        &[&self.alive, &self.name],
+                    )
+                },
+            ),
+        )
     }
     fn query_one(
         &self,
-        connection: &mut impl postgres_named_parameters::postgres::GenericClient,
-    ) -> Result<Self::Row, postgres_named_parameters::postgres::error::Error> {
-        let row = connection.query_one(
-            "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Self::Row, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeople",
+                method: "query_one",
+                sql: "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+            },
+            |_: &Self::Row| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeople",
+                "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        Self::Row,
+                        _,
+                    >(
+                        connection,
+                        "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+                        &[&self.alive, &self.name],
+                    )
+                },
+            ),
+        )
+    }
+    fn query_exists(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<bool, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeople",
+                method: "query_exists",
+                sql: "SELECT EXISTS (SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1\n)",
+            },
+            |_: &bool| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeople",
+                "SELECT EXISTS (SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1\n)",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        bool,
+                        _,
+                    >(
+                        connection,
+                        "SELECT EXISTS (SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1\n)",
+                        &[&self.alive, &self.name],
+                    )
+                },
+            ),
+        )
+    }
+    fn query_count(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<i64, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeople",
+                method: "query_count",
+                sql: "SELECT count(*) FROM (SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1\n) _",
+            },
+            |_: &i64| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeople",
+                "SELECT count(*) FROM (SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1\n) _",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        i64,
+                        _,
+                    >(
+                        connection,
+                        "SELECT count(*) FROM (SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1\n) _",
+                        &[&self.alive, &self.name],
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Explain for GetPeople<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeople",
+                method: "explain",
+                sql: "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeople",
+                "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1",
+                        &[&self.alive, &self.name],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::QueryMeta for GetPeople<'a> {
+    const NAME: &'static str = "GetPeople";
+    const ORIGINAL_SQL: &'static str = "SELECT * FROM Person WHERE (first_name = @name OR last_name = @name) AND alive = @alive";
+    const NUMBERED_SQL: &'static str = "SELECT * FROM Person WHERE (first_name = $2 OR last_name = $2) AND alive = $1";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[
+        ("alive", "bool"),
+        ("name", "&'a str"),
+    ];
+    const ROW_TYPE: Option<&'static str> = Some("Person");
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for GetPeople<'a> {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "alive",
+                    "bool",
+                    <bool as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+                (
+                    "name",
+                    "&'a str",
+                    <&'a str as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        use postgres_named_parameters::internal::registry_for_derive_macro::{
+            CheckDecodeRow as _, CheckRowColumns as _,
+        };
+        (&&postgres_named_parameters::internal::registry_for_derive_macro::ColumnChecker::<
+            Person,
+        >::new())
+            .check_columns(columns)
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::DebugSql for GetPeople<'a> {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &[
+                "SELECT * FROM Person WHERE (first_name = ",
+                " OR last_name = ",
+                ") AND alive = ",
+                "",
+            ],
+            &[1usize, 1usize, 0usize],
+            &[],
             &[&self.alive, &self.name],
-        )?;
-        postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<Self::Row>(
-            &row,
         )
     }
 }
@@ -0,0 +1,835 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+#[query(
+    sql = "SELECT id, first_name FROM Person WHERE alive = @alive",
+    row = (i32, String),
+    paginate = keyset(order_by = "first_name, id", total)
+)]
+struct GetPeople {
+    alive: bool,
+}
+#[automatically_derived]
+impl postgres_named_parameters::Query for GetPeople {
+    type Row = (i32, String);
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    const READ_ONLY: bool = false;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT id, first_name FROM Person WHERE alive = $1",
+    );
+    const EXISTS_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT EXISTS (SELECT id, first_name FROM Person WHERE alive = $1\n)",
+    );
+    const COUNT_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT count(*) FROM (SELECT id, first_name FROM Person WHERE alive = $1\n) _",
+    );
+    fn query_all(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Vec<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeople",
+                method: "query_all",
+                sql: "SELECT id, first_name FROM Person WHERE alive = $1",
+            },
+            |rows: &Vec<Self::Row>| rows.len() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeople",
+                "SELECT id, first_name FROM Person WHERE alive = $1",
+                || {
+                    let rows = connection
+                        .query(
+                            "SELECT id, first_name FROM Person WHERE alive = $1",
+                            &[&self.alive],
+                        )?;
+                    rows.iter()
+                        .map(
+                            postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<
+                                Self::Row,
+                                _,
+                            >,
+                        )
+                        .collect()
+                },
+            ),
+        )
+    }
+    fn query_opt(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Option<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeople",
+                method: "query_opt",
+                sql: "SELECT id, first_name FROM Person WHERE alive = $1",
+            },
+            |row: &Option<Self::Row>| row.is_some() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeople",
+                "SELECT id, first_name FROM Person WHERE alive = $1",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_opt::<
+                        Self::Row,
+                        _,
+                    >(
+                        connection,
+                        "SELECT id, first_name FROM Person WHERE alive = $1",
+                        &[&self.alive],
+                    )
+                },
+            ),
+        )
+    }
+    fn query_one(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Self::Row, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeople",
+                method: "query_one",
+                sql: "SELECT id, first_name FROM Person WHERE alive = $1",
+            },
+            |_: &Self::Row| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeople",
+                "SELECT id, first_name FROM Person WHERE alive = $1",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        Self::Row,
+                        _,
+                    >(
+                        connection,
+                        "SELECT id, first_name FROM Person WHERE alive = $1",
+                        &[&self.alive],
+                    )
+                },
+            ),
+        )
+    }
+    fn query_exists(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<bool, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeople",
+                method: "query_exists",
+                sql: "SELECT EXISTS (SELECT id, first_name FROM Person WHERE alive = $1\n)",
+            },
+            |_: &bool| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeople",
+                "SELECT EXISTS (SELECT id, first_name FROM Person WHERE alive = $1\n)",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        bool,
+                        _,
+                    >(
+                        connection,
+                        "SELECT EXISTS (SELECT id, first_name FROM Person WHERE alive = $1\n)",
+                        &[&self.alive],
+                    )
+                },
+            ),
+        )
+    }
+    fn query_count(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<i64, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeople",
+                method: "query_count",
+                sql: "SELECT count(*) FROM (SELECT id, first_name FROM Person WHERE alive = $1\n) _",
+            },
+            |_: &i64| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeople",
+                "SELECT count(*) FROM (SELECT id, first_name FROM Person WHERE alive = $1\n) _",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        i64,
+                        _,
+                    >(
+                        connection,
+                        "SELECT count(*) FROM (SELECT id, first_name FROM Person WHERE alive = $1\n) _",
+                        &[&self.alive],
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::Explain for GetPeople {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeople",
+                method: "explain",
+                sql: "SELECT id, first_name FROM Person WHERE alive = $1",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeople",
+                "SELECT id, first_name FROM Person WHERE alive = $1",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "SELECT id, first_name FROM Person WHERE alive = $1",
+                        &[&self.alive],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::QueryMeta for GetPeople {
+    const NAME: &'static str = "GetPeople";
+    const ORIGINAL_SQL: &'static str = "SELECT id, first_name FROM Person WHERE alive = @alive";
+    const NUMBERED_SQL: &'static str = "SELECT id, first_name FROM Person WHERE alive = $1";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("alive", "bool")];
+    const ROW_TYPE: Option<&'static str> = Some("(i32, String)");
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for GetPeople {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "alive",
+                    "bool",
+                    <bool as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        use postgres_named_parameters::internal::registry_for_derive_macro::{
+            CheckDecodeRow as _, CheckRowColumns as _,
+        };
+        (&&postgres_named_parameters::internal::registry_for_derive_macro::ColumnChecker::<
+            (i32, String),
+        >::new())
+            .check_columns(columns)
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::DebugSql for GetPeople {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["SELECT id, first_name FROM Person WHERE alive = ", ""],
+            &[0usize],
+            &[],
+            &[&self.alive],
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::PaginatedQuery for GetPeople {
+    type Cursor = postgres_named_parameters::KeysetCursor;
+    const PAGE_FINGERPRINTS: &'static [u64] = &[
+        postgres_named_parameters::sql_fingerprint(
+            "SELECT * FROM (SELECT id, first_name FROM Person WHERE alive = $1\n) _page ORDER BY first_name, id LIMIT $2",
+        ),
+        postgres_named_parameters::sql_fingerprint(
+            "SELECT * FROM (SELECT id, first_name FROM Person WHERE alive = $1\n) _page WHERE (first_name, id) > ($2, $3) ORDER BY first_name, id LIMIT $4",
+        ),
+    ];
+    fn query_page(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        cursor: Option<&Self::Cursor>,
+        limit: i64,
+    ) -> Result<
+        postgres_named_parameters::Page<Self::Row, Self::Cursor>,
+        postgres_named_parameters::Error,
+    > {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeople",
+                method: "query_page",
+                sql: "SELECT id, first_name FROM Person WHERE alive = $1",
+            },
+            |page: &postgres_named_parameters::Page<Self::Row, Self::Cursor>| {
+                page.rows.len() as u64
+            },
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeople",
+                "SELECT id, first_name FROM Person WHERE alive = $1",
+                || {
+                    let mut page = postgres_named_parameters::internal::pagination_for_derive_macro::query_keyset_page::<
+                        Self::Row,
+                        _,
+                    >(
+                        connection,
+                        "SELECT * FROM (SELECT id, first_name FROM Person WHERE alive = $1\n) _page ORDER BY first_name, id LIMIT $2",
+                        "SELECT * FROM (SELECT id, first_name FROM Person WHERE alive = $1\n) _page WHERE (first_name, id) > ($2, $3) ORDER BY first_name, id LIMIT $4",
+                        &[&self.alive],
+                        &["first_name", "id"],
+                        cursor,
+                        limit,
+                    )?;
+                    page.total = Some(
+                        postgres_named_parameters::Query::query_count(self, connection)?,
+                    );
+                    Ok(page)
+                },
+            ),
+        )
+    }
+}
+#[query(sql = "SELECT id FROM Person ORDER BY id", row = i32, paginate = offset)]
+struct GetPersonIds;
+#[automatically_derived]
+impl postgres_named_parameters::Query for GetPersonIds {
+    type Row = i32;
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    const READ_ONLY: bool = false;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT id FROM Person ORDER BY id",
+    );
+    const EXISTS_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT EXISTS (SELECT id FROM Person ORDER BY id\n)",
+    );
+    const COUNT_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT count(*) FROM (SELECT id FROM Person ORDER BY id\n) _",
+    );
+    fn query_all(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Vec<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPersonIds",
+                method: "query_all",
+                sql: "SELECT id FROM Person ORDER BY id",
+            },
+            |rows: &Vec<Self::Row>| rows.len() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPersonIds",
+                "SELECT id FROM Person ORDER BY id",
+                || {
+                    let rows = connection
+                        .query("SELECT id FROM Person ORDER BY id", &[])?;
+                    rows.iter()
+                        .map(
+                            postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<
+                                Self::Row,
+                                _,
+                            >,
+                        )
+                        .collect()
+                },
+            ),
+        )
+    }
+    fn query_opt(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Option<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPersonIds",
+                method: "query_opt",
+                sql: "SELECT id FROM Person ORDER BY id",
+            },
+            |row: &Option<Self::Row>| row.is_some() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPersonIds",
+                "SELECT id FROM Person ORDER BY id",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_opt::<
+                        Self::Row,
+                        _,
+                    >(connection, "SELECT id FROM Person ORDER BY id", &[])
+                },
+            ),
+        )
+    }
+    fn query_one(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Self::Row, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPersonIds",
+                method: "query_one",
+                sql: "SELECT id FROM Person ORDER BY id",
+            },
+            |_: &Self::Row| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPersonIds",
+                "SELECT id FROM Person ORDER BY id",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        Self::Row,
+                        _,
+                    >(connection, "SELECT id FROM Person ORDER BY id", &[])
+                },
+            ),
+        )
+    }
+    fn query_exists(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<bool, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPersonIds",
+                method: "query_exists",
+                sql: "SELECT EXISTS (SELECT id FROM Person ORDER BY id\n)",
+            },
+            |_: &bool| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPersonIds",
+                "SELECT EXISTS (SELECT id FROM Person ORDER BY id\n)",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        bool,
+                        _,
+                    >(
+                        connection,
+                        "SELECT EXISTS (SELECT id FROM Person ORDER BY id\n)",
+                        &[],
+                    )
+                },
+            ),
+        )
+    }
+    fn query_count(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<i64, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPersonIds",
+                method: "query_count",
+                sql: "SELECT count(*) FROM (SELECT id FROM Person ORDER BY id\n) _",
+            },
+            |_: &i64| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPersonIds",
+                "SELECT count(*) FROM (SELECT id FROM Person ORDER BY id\n) _",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        i64,
+                        _,
+                    >(
+                        connection,
+                        "SELECT count(*) FROM (SELECT id FROM Person ORDER BY id\n) _",
+                        &[],
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::Explain for GetPersonIds {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPersonIds",
+                method: "explain",
+                sql: "SELECT id FROM Person ORDER BY id",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPersonIds",
+                "SELECT id FROM Person ORDER BY id",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "SELECT id FROM Person ORDER BY id",
+                        &[],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::QueryMeta for GetPersonIds {
+    const NAME: &'static str = "GetPersonIds";
+    const ORIGINAL_SQL: &'static str = "SELECT id FROM Person ORDER BY id";
+    const NUMBERED_SQL: &'static str = "SELECT id FROM Person ORDER BY id";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[];
+    const ROW_TYPE: Option<&'static str> = Some("i32");
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for GetPersonIds {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        use postgres_named_parameters::internal::registry_for_derive_macro::{
+            CheckDecodeRow as _, CheckRowColumns as _,
+        };
+        (&&postgres_named_parameters::internal::registry_for_derive_macro::ColumnChecker::<
+            i32,
+        >::new())
+            .check_columns(columns)
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::DebugSql for GetPersonIds {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["SELECT id FROM Person ORDER BY id"],
+            &[],
+            &[],
+            &[],
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::PaginatedQuery for GetPersonIds {
+    type Cursor = postgres_named_parameters::OffsetCursor;
+    const PAGE_FINGERPRINTS: &'static [u64] = &[
+        postgres_named_parameters::sql_fingerprint(
+            "SELECT * FROM (SELECT id FROM Person ORDER BY id\n) _page LIMIT $1 OFFSET $2",
+        ),
+    ];
+    fn query_page(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        cursor: Option<&Self::Cursor>,
+        limit: i64,
+    ) -> Result<
+        postgres_named_parameters::Page<Self::Row, Self::Cursor>,
+        postgres_named_parameters::Error,
+    > {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPersonIds",
+                method: "query_page",
+                sql: "SELECT id FROM Person ORDER BY id",
+            },
+            |page: &postgres_named_parameters::Page<Self::Row, Self::Cursor>| {
+                page.rows.len() as u64
+            },
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPersonIds",
+                "SELECT id FROM Person ORDER BY id",
+                || {
+                    postgres_named_parameters::internal::pagination_for_derive_macro::query_offset_page::<
+                        Self::Row,
+                        _,
+                    >(
+                        connection,
+                        "SELECT * FROM (SELECT id FROM Person ORDER BY id\n) _page LIMIT $1 OFFSET $2",
+                        &[],
+                        cursor,
+                        limit,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[query(
+    sql = "SELECT id, first_name FROM Person",
+    row = (i32, String),
+    paginate = offset(order_by = "first_name DESC, id", total)
+)]
+struct GetPeopleByName;
+#[automatically_derived]
+impl postgres_named_parameters::Query for GetPeopleByName {
+    type Row = (i32, String);
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    const READ_ONLY: bool = false;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT id, first_name FROM Person",
+    );
+    const EXISTS_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT EXISTS (SELECT id, first_name FROM Person\n)",
+    );
+    const COUNT_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT count(*) FROM (SELECT id, first_name FROM Person\n) _",
+    );
+    fn query_all(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Vec<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeopleByName",
+                method: "query_all",
+                sql: "SELECT id, first_name FROM Person",
+            },
+            |rows: &Vec<Self::Row>| rows.len() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeopleByName",
+                "SELECT id, first_name FROM Person",
+                || {
+                    let rows = connection
+                        .query("SELECT id, first_name FROM Person", &[])?;
+                    rows.iter()
+                        .map(
+                            postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<
+                                Self::Row,
+                                _,
+                            >,
+                        )
+                        .collect()
+                },
+            ),
+        )
+    }
+    fn query_opt(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Option<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeopleByName",
+                method: "query_opt",
+                sql: "SELECT id, first_name FROM Person",
+            },
+            |row: &Option<Self::Row>| row.is_some() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeopleByName",
+                "SELECT id, first_name FROM Person",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_opt::<
+                        Self::Row,
+                        _,
+                    >(connection, "SELECT id, first_name FROM Person", &[])
+                },
+            ),
+        )
+    }
+    fn query_one(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Self::Row, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeopleByName",
+                method: "query_one",
+                sql: "SELECT id, first_name FROM Person",
+            },
+            |_: &Self::Row| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeopleByName",
+                "SELECT id, first_name FROM Person",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        Self::Row,
+                        _,
+                    >(connection, "SELECT id, first_name FROM Person", &[])
+                },
+            ),
+        )
+    }
+    fn query_exists(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<bool, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeopleByName",
+                method: "query_exists",
+                sql: "SELECT EXISTS (SELECT id, first_name FROM Person\n)",
+            },
+            |_: &bool| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeopleByName",
+                "SELECT EXISTS (SELECT id, first_name FROM Person\n)",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        bool,
+                        _,
+                    >(
+                        connection,
+                        "SELECT EXISTS (SELECT id, first_name FROM Person\n)",
+                        &[],
+                    )
+                },
+            ),
+        )
+    }
+    fn query_count(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<i64, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeopleByName",
+                method: "query_count",
+                sql: "SELECT count(*) FROM (SELECT id, first_name FROM Person\n) _",
+            },
+            |_: &i64| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeopleByName",
+                "SELECT count(*) FROM (SELECT id, first_name FROM Person\n) _",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        i64,
+                        _,
+                    >(
+                        connection,
+                        "SELECT count(*) FROM (SELECT id, first_name FROM Person\n) _",
+                        &[],
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::Explain for GetPeopleByName {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeopleByName",
+                method: "explain",
+                sql: "SELECT id, first_name FROM Person",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeopleByName",
+                "SELECT id, first_name FROM Person",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "SELECT id, first_name FROM Person",
+                        &[],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::QueryMeta for GetPeopleByName {
+    const NAME: &'static str = "GetPeopleByName";
+    const ORIGINAL_SQL: &'static str = "SELECT id, first_name FROM Person";
+    const NUMBERED_SQL: &'static str = "SELECT id, first_name FROM Person";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[];
+    const ROW_TYPE: Option<&'static str> = Some("(i32, String)");
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for GetPeopleByName {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        use postgres_named_parameters::internal::registry_for_derive_macro::{
+            CheckDecodeRow as _, CheckRowColumns as _,
+        };
+        (&&postgres_named_parameters::internal::registry_for_derive_macro::ColumnChecker::<
+            (i32, String),
+        >::new())
+            .check_columns(columns)
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::DebugSql for GetPeopleByName {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["SELECT id, first_name FROM Person"],
+            &[],
+            &[],
+            &[],
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::PaginatedQuery for GetPeopleByName {
+    type Cursor = postgres_named_parameters::OffsetCursor;
+    const PAGE_FINGERPRINTS: &'static [u64] = &[
+        postgres_named_parameters::sql_fingerprint(
+            "SELECT * FROM (SELECT id, first_name FROM Person\n) _page ORDER BY first_name DESC, id LIMIT $1 OFFSET $2",
+        ),
+    ];
+    fn query_page(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        cursor: Option<&Self::Cursor>,
+        limit: i64,
+    ) -> Result<
+        postgres_named_parameters::Page<Self::Row, Self::Cursor>,
+        postgres_named_parameters::Error,
+    > {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetPeopleByName",
+                method: "query_page",
+                sql: "SELECT id, first_name FROM Person",
+            },
+            |page: &postgres_named_parameters::Page<Self::Row, Self::Cursor>| {
+                page.rows.len() as u64
+            },
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "GetPeopleByName",
+                "SELECT id, first_name FROM Person",
+                || {
+                    let mut page = postgres_named_parameters::internal::pagination_for_derive_macro::query_offset_page::<
+                        Self::Row,
+                        _,
+                    >(
+                        connection,
+                        "SELECT * FROM (SELECT id, first_name FROM Person\n) _page ORDER BY first_name DESC, id LIMIT $1 OFFSET $2",
+                        &[],
+                        cursor,
+                        limit,
+                    )?;
+                    page.total = Some(
+                        postgres_named_parameters::Query::query_count(self, connection)?,
+                    );
+                    Ok(page)
+                },
+            ),
+        )
+    }
+}
@@ -0,0 +1,29 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+
+#[derive(Query)]
+#[query(
+    sql = "SELECT id, first_name FROM Person WHERE alive = @alive",
+    row = (i32, String),
+    paginate = keyset(order_by = "first_name, id", total)
+)]
+struct GetPeople {
+    alive: bool,
+}
+
+#[derive(Query)]
+#[query(
+    sql = "SELECT id FROM Person ORDER BY id",
+    row = i32,
+    paginate = offset
+)]
+struct GetPersonIds;
+
+#[derive(Query)]
+#[query(
+    sql = "SELECT id, first_name FROM Person",
+    row = (i32, String),
+    paginate = offset(order_by = "first_name DESC, id", total)
+)]
+struct GetPeopleByName;
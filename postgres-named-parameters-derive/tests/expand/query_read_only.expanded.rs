@@ -0,0 +1,291 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+#[query(sql = "SELECT count(*) FROM Person WHERE alive = @alive", row = i64, read_only)]
+struct CountPeople {
+    alive: bool,
+}
+#[automatically_derived]
+impl postgres_named_parameters::Query for CountPeople {
+    type Row = i64;
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    const READ_ONLY: bool = true;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT count(*) FROM Person WHERE alive = $1",
+    );
+    const EXISTS_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT EXISTS (SELECT count(*) FROM Person WHERE alive = $1\n)",
+    );
+    const COUNT_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT count(*) FROM (SELECT count(*) FROM Person WHERE alive = $1\n) _",
+    );
+    fn query_all(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Vec<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "CountPeople",
+                method: "query_all",
+                sql: "SELECT count(*) FROM Person WHERE alive = $1",
+            },
+            |rows: &Vec<Self::Row>| rows.len() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "CountPeople",
+                "SELECT count(*) FROM Person WHERE alive = $1",
+                || {
+                    postgres_named_parameters::internal::read_only_for_derive_macro::with_read_only::<
+                        _,
+                        Self::Requires,
+                        _,
+                        _,
+                    >(
+                        connection,
+                        |connection| {
+                            let rows = connection
+                                .query(
+                                    "SELECT count(*) FROM Person WHERE alive = $1",
+                                    &[&self.alive],
+                                )?;
+                            rows.iter()
+                                .map(
+                                    postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<
+                                        Self::Row,
+                                        _,
+                                    >,
+                                )
+                                .collect()
+                        },
+                    )
+                },
+            ),
+        )
+    }
+    fn query_opt(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Option<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "CountPeople",
+                method: "query_opt",
+                sql: "SELECT count(*) FROM Person WHERE alive = $1",
+            },
+            |row: &Option<Self::Row>| row.is_some() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "CountPeople",
+                "SELECT count(*) FROM Person WHERE alive = $1",
+                || {
+                    postgres_named_parameters::internal::read_only_for_derive_macro::with_read_only::<
+                        _,
+                        Self::Requires,
+                        _,
+                        _,
+                    >(
+                        connection,
+                        |connection| {
+                            postgres_named_parameters::internal::wrapper_for_derive_macro::query_opt::<
+                                Self::Row,
+                                _,
+                            >(
+                                connection,
+                                "SELECT count(*) FROM Person WHERE alive = $1",
+                                &[&self.alive],
+                            )
+                        },
+                    )
+                },
+            ),
+        )
+    }
+    fn query_one(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Self::Row, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "CountPeople",
+                method: "query_one",
+                sql: "SELECT count(*) FROM Person WHERE alive = $1",
+            },
+            |_: &Self::Row| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "CountPeople",
+                "SELECT count(*) FROM Person WHERE alive = $1",
+                || {
+                    postgres_named_parameters::internal::read_only_for_derive_macro::with_read_only::<
+                        _,
+                        Self::Requires,
+                        _,
+                        _,
+                    >(
+                        connection,
+                        |connection| {
+                            postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                                Self::Row,
+                                _,
+                            >(
+                                connection,
+                                "SELECT count(*) FROM Person WHERE alive = $1",
+                                &[&self.alive],
+                            )
+                        },
+                    )
+                },
+            ),
+        )
+    }
+    fn query_exists(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<bool, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "CountPeople",
+                method: "query_exists",
+                sql: "SELECT EXISTS (SELECT count(*) FROM Person WHERE alive = $1\n)",
+            },
+            |_: &bool| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "CountPeople",
+                "SELECT EXISTS (SELECT count(*) FROM Person WHERE alive = $1\n)",
+                || {
+                    postgres_named_parameters::internal::read_only_for_derive_macro::with_read_only::<
+                        _,
+                        Self::Requires,
+                        _,
+                        _,
+                    >(
+                        connection,
+                        |connection| {
+                            postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                                bool,
+                                _,
+                            >(
+                                connection,
+                                "SELECT EXISTS (SELECT count(*) FROM Person WHERE alive = $1\n)",
+                                &[&self.alive],
+                            )
+                        },
+                    )
+                },
+            ),
+        )
+    }
+    fn query_count(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<i64, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "CountPeople",
+                method: "query_count",
+                sql: "SELECT count(*) FROM (SELECT count(*) FROM Person WHERE alive = $1\n) _",
+            },
+            |_: &i64| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "CountPeople",
+                "SELECT count(*) FROM (SELECT count(*) FROM Person WHERE alive = $1\n) _",
+                || {
+                    postgres_named_parameters::internal::read_only_for_derive_macro::with_read_only::<
+                        _,
+                        Self::Requires,
+                        _,
+                        _,
+                    >(
+                        connection,
+                        |connection| {
+                            postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                                i64,
+                                _,
+                            >(
+                                connection,
+                                "SELECT count(*) FROM (SELECT count(*) FROM Person WHERE alive = $1\n) _",
+                                &[&self.alive],
+                            )
+                        },
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::Explain for CountPeople {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "CountPeople",
+                method: "explain",
+                sql: "SELECT count(*) FROM Person WHERE alive = $1",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "CountPeople",
+                "SELECT count(*) FROM Person WHERE alive = $1",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "SELECT count(*) FROM Person WHERE alive = $1",
+                        &[&self.alive],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::QueryMeta for CountPeople {
+    const NAME: &'static str = "CountPeople";
+    const ORIGINAL_SQL: &'static str = "SELECT count(*) FROM Person WHERE alive = @alive";
+    const NUMBERED_SQL: &'static str = "SELECT count(*) FROM Person WHERE alive = $1";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("alive", "bool")];
+    const ROW_TYPE: Option<&'static str> = Some("i64");
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for CountPeople {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "alive",
+                    "bool",
+                    <bool as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        use postgres_named_parameters::internal::registry_for_derive_macro::{
+            CheckDecodeRow as _, CheckRowColumns as _,
+        };
+        (&&postgres_named_parameters::internal::registry_for_derive_macro::ColumnChecker::<
+            i64,
+        >::new())
+            .check_columns(columns)
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::DebugSql for CountPeople {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["SELECT count(*) FROM Person WHERE alive = ", ""],
+            &[0usize],
+            &[],
+            &[&self.alive],
+        )
+    }
+}
@@ -0,0 +1,9 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+
+#[derive(Query)]
+#[query(sql = "SELECT count(*) FROM Person WHERE alive = @alive", row = i64, read_only)]
+struct CountPeople {
+    alive: bool,
+}
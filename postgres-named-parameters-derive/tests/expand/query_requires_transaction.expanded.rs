@@ -0,0 +1,245 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+#[query(
+    sql = "SELECT balance FROM Account WHERE id = @id FOR UPDATE",
+    row = i64,
+    requires_transaction
+)]
+struct LockAccount {
+    id: i32,
+}
+#[automatically_derived]
+impl postgres_named_parameters::Query for LockAccount {
+    type Row = i64;
+    type Requires = postgres_named_parameters::connection::InTransaction;
+    const READ_ONLY: bool = false;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT balance FROM Account WHERE id = $1 FOR UPDATE",
+    );
+    const EXISTS_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT EXISTS (SELECT balance FROM Account WHERE id = $1 FOR UPDATE\n)",
+    );
+    const COUNT_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT count(*) FROM (SELECT balance FROM Account WHERE id = $1 FOR UPDATE\n) _",
+    );
+    fn query_all(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Vec<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "LockAccount",
+                method: "query_all",
+                sql: "SELECT balance FROM Account WHERE id = $1 FOR UPDATE",
+            },
+            |rows: &Vec<Self::Row>| rows.len() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "LockAccount",
+                "SELECT balance FROM Account WHERE id = $1 FOR UPDATE",
+                || {
+                    let rows = connection
+                        .query(
+                            "SELECT balance FROM Account WHERE id = $1 FOR UPDATE",
+                            &[&self.id],
+                        )?;
+                    rows.iter()
+                        .map(
+                            postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<
+                                Self::Row,
+                                _,
+                            >,
+                        )
+                        .collect()
+                },
+            ),
+        )
+    }
+    fn query_opt(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Option<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "LockAccount",
+                method: "query_opt",
+                sql: "SELECT balance FROM Account WHERE id = $1 FOR UPDATE",
+            },
+            |row: &Option<Self::Row>| row.is_some() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "LockAccount",
+                "SELECT balance FROM Account WHERE id = $1 FOR UPDATE",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_opt::<
+                        Self::Row,
+                        _,
+                    >(
+                        connection,
+                        "SELECT balance FROM Account WHERE id = $1 FOR UPDATE",
+                        &[&self.id],
+                    )
+                },
+            ),
+        )
+    }
+    fn query_one(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Self::Row, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "LockAccount",
+                method: "query_one",
+                sql: "SELECT balance FROM Account WHERE id = $1 FOR UPDATE",
+            },
+            |_: &Self::Row| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "LockAccount",
+                "SELECT balance FROM Account WHERE id = $1 FOR UPDATE",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        Self::Row,
+                        _,
+                    >(
+                        connection,
+                        "SELECT balance FROM Account WHERE id = $1 FOR UPDATE",
+                        &[&self.id],
+                    )
+                },
+            ),
+        )
+    }
+    fn query_exists(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<bool, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "LockAccount",
+                method: "query_exists",
+                sql: "SELECT EXISTS (SELECT balance FROM Account WHERE id = $1 FOR UPDATE\n)",
+            },
+            |_: &bool| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "LockAccount",
+                "SELECT EXISTS (SELECT balance FROM Account WHERE id = $1 FOR UPDATE\n)",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        bool,
+                        _,
+                    >(
+                        connection,
+                        "SELECT EXISTS (SELECT balance FROM Account WHERE id = $1 FOR UPDATE\n)",
+                        &[&self.id],
+                    )
+                },
+            ),
+        )
+    }
+    fn query_count(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<i64, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "LockAccount",
+                method: "query_count",
+                sql: "SELECT count(*) FROM (SELECT balance FROM Account WHERE id = $1 FOR UPDATE\n) _",
+            },
+            |_: &i64| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "LockAccount",
+                "SELECT count(*) FROM (SELECT balance FROM Account WHERE id = $1 FOR UPDATE\n) _",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        i64,
+                        _,
+                    >(
+                        connection,
+                        "SELECT count(*) FROM (SELECT balance FROM Account WHERE id = $1 FOR UPDATE\n) _",
+                        &[&self.id],
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::Explain for LockAccount {
+    type Requires = postgres_named_parameters::connection::InTransaction;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "LockAccount",
+                method: "explain",
+                sql: "SELECT balance FROM Account WHERE id = $1 FOR UPDATE",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "LockAccount",
+                "SELECT balance FROM Account WHERE id = $1 FOR UPDATE",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "SELECT balance FROM Account WHERE id = $1 FOR UPDATE",
+                        &[&self.id],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::QueryMeta for LockAccount {
+    const NAME: &'static str = "LockAccount";
+    const ORIGINAL_SQL: &'static str = "SELECT balance FROM Account WHERE id = @id FOR UPDATE";
+    const NUMBERED_SQL: &'static str = "SELECT balance FROM Account WHERE id = $1 FOR UPDATE";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("id", "i32")];
+    const ROW_TYPE: Option<&'static str> = Some("i64");
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for LockAccount {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "id",
+                    "i32",
+                    <i32 as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        use postgres_named_parameters::internal::registry_for_derive_macro::{
+            CheckDecodeRow as _, CheckRowColumns as _,
+        };
+        (&&postgres_named_parameters::internal::registry_for_derive_macro::ColumnChecker::<
+            i64,
+        >::new())
+            .check_columns(columns)
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::DebugSql for LockAccount {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["SELECT balance FROM Account WHERE id = ", " FOR UPDATE"],
+            &[0usize],
+            &[],
+            &[&self.id],
+        )
+    }
+}
@@ -0,0 +1,13 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+
+#[derive(Query)]
+#[query(
+    sql = "SELECT balance FROM Account WHERE id = @id FOR UPDATE",
+    row = i64,
+    requires_transaction
+)]
+struct LockAccount {
+    id: i32,
+}
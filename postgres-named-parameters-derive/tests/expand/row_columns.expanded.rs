@@ -0,0 +1,52 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+struct Person {
+    id: i32,
+    #[from_row(rename = "first_name")]
+    name: String,
+    #[from_row(from = "i32")]
+    age: i64,
+    #[from_row(flatten)]
+    address: Address,
+}
+#[automatically_derived]
+impl postgres_named_parameters::RowColumns for Person
+where
+    i32: postgres_named_parameters::postgres::types::FromSqlOwned,
+    String: postgres_named_parameters::postgres::types::FromSqlOwned,
+    i32: postgres_named_parameters::postgres::types::FromSqlOwned,
+    Address: postgres_named_parameters::RowColumns,
+{
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        let mut problems = Vec::new();
+        problems
+            .extend(
+                postgres_named_parameters::internal::row_columns_for_derive_macro::check_named_column::<
+                    i32,
+                >(columns, "id"),
+            );
+        problems
+            .extend(
+                postgres_named_parameters::internal::row_columns_for_derive_macro::check_named_column::<
+                    String,
+                >(columns, "first_name"),
+            );
+        problems
+            .extend(
+                postgres_named_parameters::internal::row_columns_for_derive_macro::check_named_column::<
+                    i32,
+                >(columns, "age"),
+            );
+        problems
+            .extend(
+                <Address as postgres_named_parameters::RowColumns>::check_columns(
+                    columns,
+                ),
+            );
+        problems
+    }
+}
+struct Address;
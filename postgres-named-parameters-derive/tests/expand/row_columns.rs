@@ -0,0 +1,16 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+
+#[derive(RowColumns)]
+struct Person {
+    id: i32,
+    #[from_row(rename = "first_name")]
+    name: String,
+    #[from_row(from = "i32")]
+    age: i64,
+    #[from_row(flatten)]
+    address: Address,
+}
+
+struct Address;
@@ -1,16 +1,115 @@
 #[macro_use]
 extern crate postgres_named_parameters_derive;
 use postgres_named_parameters::*;
+struct Person;
 #[statement(sql = "INSERT INTO Person VALUES @people")]
 struct InsertPeople {
     people: Vec<Person>,
 }
 #[automatically_derived]
 impl postgres_named_parameters::Statement for InsertPeople {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    type Error = postgres_named_parameters::Error;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "INSERT INTO Person VALUES $1",
+    );
     fn execute_statement(
         &self,
-        connection: &mut impl postgres_named_parameters::postgres::GenericClient,
-    ) -> Result<u64, postgres_named_parameters::postgres::error::Error> {
-        connection.execute("INSERT INTO Person VALUES $1", &[&self.people])
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<u64, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPeople",
+                method: "execute_statement",
+                sql: "INSERT INTO Person VALUES $1",
+            },
+            |rows_affected: &u64| *rows_affected,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPeople",
+                "INSERT INTO Person VALUES $1",
+                || {
+                    Ok(
+                        connection
+                            .execute("INSERT INTO Person VALUES $1", &[&self.people])?,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::Explain for InsertPeople {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPeople",
+                method: "explain",
+                sql: "INSERT INTO Person VALUES $1",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPeople",
+                "INSERT INTO Person VALUES $1",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "INSERT INTO Person VALUES $1",
+                        &[&self.people],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::QueryMeta for InsertPeople {
+    const NAME: &'static str = "InsertPeople";
+    const ORIGINAL_SQL: &'static str = "INSERT INTO Person VALUES @people";
+    const NUMBERED_SQL: &'static str = "INSERT INTO Person VALUES $1";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("people", "Vec<Person>")];
+    const ROW_TYPE: Option<&'static str> = None;
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for InsertPeople {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "people",
+                    "Vec<Person>",
+                    <Vec<
+                        Person,
+                    > as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        let _ = columns;
+        Vec::new()
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::DebugSql for InsertPeople {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["INSERT INTO Person VALUES ", ""],
+            &[0usize],
+            &[],
+            &[&self.people],
+        )
     }
 }
@@ -2,6 +2,8 @@
 extern crate postgres_named_parameters_derive;
 use postgres_named_parameters::*;
 
+struct Person;
+
 #[derive(Statement)]
 #[statement(sql = "INSERT INTO Person VALUES @people")]
 struct InsertPeople {
@@ -0,0 +1,351 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+#[statement(sql = "UPDATE Person SET name = @name WHERE id = @id", expect_rows = 1)]
+struct RenamePerson<'a> {
+    id: i32,
+    name: &'a str,
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Statement for RenamePerson<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    type Error = postgres_named_parameters::Error;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "UPDATE Person SET name = $2 WHERE id = $1",
+    );
+    fn execute_statement(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<u64, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "RenamePerson",
+                method: "execute_statement",
+                sql: "UPDATE Person SET name = $2 WHERE id = $1",
+            },
+            |rows_affected: &u64| *rows_affected,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "RenamePerson",
+                "UPDATE Person SET name = $2 WHERE id = $1",
+                || {
+                    postgres_named_parameters::internal::expect_rows_for_derive_macro::with_expected_rows(
+                        connection,
+                        postgres_named_parameters::ExpectedRows {
+                            min: 1u64,
+                            max: Some(1u64),
+                        },
+                        |rows_affected: &u64| *rows_affected,
+                        |connection| {
+                            Ok(
+                                connection
+                                    .execute(
+                                        "UPDATE Person SET name = $2 WHERE id = $1",
+                                        &[&self.id, &self.name],
+                                    )?,
+                            )
+                        },
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Explain for RenamePerson<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "RenamePerson",
+                method: "explain",
+                sql: "UPDATE Person SET name = $2 WHERE id = $1",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "RenamePerson",
+                "UPDATE Person SET name = $2 WHERE id = $1",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "UPDATE Person SET name = $2 WHERE id = $1",
+                        &[&self.id, &self.name],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::QueryMeta for RenamePerson<'a> {
+    const NAME: &'static str = "RenamePerson";
+    const ORIGINAL_SQL: &'static str = "UPDATE Person SET name = @name WHERE id = @id";
+    const NUMBERED_SQL: &'static str = "UPDATE Person SET name = $2 WHERE id = $1";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[
+        ("id", "i32"),
+        ("name", "&'a str"),
+    ];
+    const ROW_TYPE: Option<&'static str> = None;
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for RenamePerson<'a> {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "id",
+                    "i32",
+                    <i32 as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+                (
+                    "name",
+                    "&'a str",
+                    <&'a str as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        let _ = columns;
+        Vec::new()
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::DebugSql for RenamePerson<'a> {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["UPDATE Person SET name = ", " WHERE id = ", ""],
+            &[1usize, 0usize],
+            &[],
+            &[&self.id, &self.name],
+        )
+    }
+}
+#[statement(
+    sql = "DELETE FROM Person WHERE id = @id RETURNING name",
+    returning = String,
+    expect_rows = "0..=1"
+)]
+struct DeletePerson {
+    id: i32,
+}
+#[automatically_derived]
+impl postgres_named_parameters::Statement for DeletePerson {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    type Error = postgres_named_parameters::Error;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "DELETE FROM Person WHERE id = $1 RETURNING name",
+    );
+    fn execute_statement(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<u64, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "DeletePerson",
+                method: "execute_statement",
+                sql: "DELETE FROM Person WHERE id = $1 RETURNING name",
+            },
+            |rows_affected: &u64| *rows_affected,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "DeletePerson",
+                "DELETE FROM Person WHERE id = $1 RETURNING name",
+                || {
+                    postgres_named_parameters::internal::expect_rows_for_derive_macro::with_expected_rows(
+                        connection,
+                        postgres_named_parameters::ExpectedRows {
+                            min: 0u64,
+                            max: Some(1u64),
+                        },
+                        |rows_affected: &u64| *rows_affected,
+                        |connection| {
+                            Ok(
+                                connection
+                                    .execute(
+                                        "DELETE FROM Person WHERE id = $1 RETURNING name",
+                                        &[&self.id],
+                                    )?,
+                            )
+                        },
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::Explain for DeletePerson {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "DeletePerson",
+                method: "explain",
+                sql: "DELETE FROM Person WHERE id = $1 RETURNING name",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "DeletePerson",
+                "DELETE FROM Person WHERE id = $1 RETURNING name",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "DELETE FROM Person WHERE id = $1 RETURNING name",
+                        &[&self.id],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::QueryMeta for DeletePerson {
+    const NAME: &'static str = "DeletePerson";
+    const ORIGINAL_SQL: &'static str = "DELETE FROM Person WHERE id = @id RETURNING name";
+    const NUMBERED_SQL: &'static str = "DELETE FROM Person WHERE id = $1 RETURNING name";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("id", "i32")];
+    const ROW_TYPE: Option<&'static str> = Some("String");
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for DeletePerson {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "id",
+                    "i32",
+                    <i32 as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        use postgres_named_parameters::internal::registry_for_derive_macro::{
+            CheckDecodeRow as _, CheckRowColumns as _,
+        };
+        (&&postgres_named_parameters::internal::registry_for_derive_macro::ColumnChecker::<
+            String,
+        >::new())
+            .check_columns(columns)
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::DebugSql for DeletePerson {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["DELETE FROM Person WHERE id = ", " RETURNING name"],
+            &[0usize],
+            &[],
+            &[&self.id],
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::ReturningStatement for DeletePerson {
+    type Row = String;
+    fn execute_returning_all(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<postgres_named_parameters::Returned<Self::Row>, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "DeletePerson",
+                method: "execute_returning_all",
+                sql: "DELETE FROM Person WHERE id = $1 RETURNING name",
+            },
+            |returned: &postgres_named_parameters::Returned<Self::Row>| {
+                returned.rows_affected
+            },
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "DeletePerson",
+                "DELETE FROM Person WHERE id = $1 RETURNING name",
+                || {
+                    postgres_named_parameters::internal::expect_rows_for_derive_macro::with_expected_rows(
+                        connection,
+                        postgres_named_parameters::ExpectedRows {
+                            min: 0u64,
+                            max: Some(1u64),
+                        },
+                        |returned: &postgres_named_parameters::Returned<Self::Row>| {
+                            returned.rows_affected
+                        },
+                        |connection| {
+                            let parameters: &[&(dyn postgres_named_parameters::postgres::types::ToSql + Sync)] = &[
+                                &self.id,
+                            ];
+                            let row_iter = connection
+                                .query_raw(
+                                    "DELETE FROM Person WHERE id = $1 RETURNING name",
+                                    parameters.iter().copied(),
+                                )?;
+                            postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row_iter::<
+                                Self::Row,
+                                _,
+                            >(row_iter)
+                        },
+                    )
+                },
+            ),
+        )
+    }
+    fn execute_returning_one(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Self::Row, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "DeletePerson",
+                method: "execute_returning_one",
+                sql: "DELETE FROM Person WHERE id = $1 RETURNING name",
+            },
+            |_: &Self::Row| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "DeletePerson",
+                "DELETE FROM Person WHERE id = $1 RETURNING name",
+                || {
+                    postgres_named_parameters::internal::expect_rows_for_derive_macro::with_expected_rows(
+                        connection,
+                        postgres_named_parameters::ExpectedRows {
+                            min: 0u64,
+                            max: Some(1u64),
+                        },
+                        |_: &Self::Row| 1,
+                        |connection| {
+                            postgres_named_parameters::internal::wrapper_for_derive_macro::execute_returning_one::<
+                                Self::Row,
+                                _,
+                            >(
+                                connection,
+                                "DELETE FROM Person WHERE id = $1 RETURNING name",
+                                &[&self.id],
+                            )
+                        },
+                    )
+                },
+            ),
+        )
+    }
+}
@@ -0,0 +1,20 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+
+#[derive(Statement)]
+#[statement(sql = "UPDATE Person SET name = @name WHERE id = @id", expect_rows = 1)]
+struct RenamePerson<'a> {
+    id: i32,
+    name: &'a str,
+}
+
+#[derive(Statement)]
+#[statement(
+    sql = "DELETE FROM Person WHERE id = @id RETURNING name",
+    returning = String,
+    expect_rows = "0..=1"
+)]
+struct DeletePerson {
+    id: i32,
+}
@@ -0,0 +1,147 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+enum PersonError {
+    EmailTaken,
+    NoSuchTeam,
+}
+#[statement(
+    sql = "INSERT INTO Person (email, team_id) VALUES (@email, @team_id)",
+    on_constraint(
+        "person_email_key" = >PersonError::EmailTaken,
+        "person_team_id_fkey" = >PersonError::NoSuchTeam,
+    )
+)]
+struct InsertPerson<'a> {
+    email: &'a str,
+    team_id: i32,
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Statement for InsertPerson<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    type Error = postgres_named_parameters::StatementError<PersonError>;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "INSERT INTO Person (email, team_id) VALUES ($1, $2)",
+    );
+    fn execute_statement(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<u64, Self::Error> {
+        (::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPerson",
+                method: "execute_statement",
+                sql: "INSERT INTO Person (email, team_id) VALUES ($1, $2)",
+            },
+            |rows_affected: &u64| *rows_affected,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPerson",
+                "INSERT INTO Person (email, team_id) VALUES ($1, $2)",
+                || {
+                    Ok(
+                        connection
+                            .execute(
+                                "INSERT INTO Person (email, team_id) VALUES ($1, $2)",
+                                &[&self.email, &self.team_id],
+                            )?,
+                    )
+                },
+            ),
+        ))
+            .map_err(|error| {
+                let violation = match postgres_named_parameters::internal::constraint_for_derive_macro::violated_constraint(
+                    &error,
+                ) {
+                    Some("person_email_key") => Some(PersonError::EmailTaken),
+                    Some("person_team_id_fkey") => Some(PersonError::NoSuchTeam),
+                    _ => None,
+                };
+                postgres_named_parameters::internal::constraint_for_derive_macro::statement_error(
+                    error,
+                    violation,
+                )
+            })
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Explain for InsertPerson<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPerson",
+                method: "explain",
+                sql: "INSERT INTO Person (email, team_id) VALUES ($1, $2)",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPerson",
+                "INSERT INTO Person (email, team_id) VALUES ($1, $2)",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "INSERT INTO Person (email, team_id) VALUES ($1, $2)",
+                        &[&self.email, &self.team_id],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::QueryMeta for InsertPerson<'a> {
+    const NAME: &'static str = "InsertPerson";
+    const ORIGINAL_SQL: &'static str = "INSERT INTO Person (email, team_id) VALUES (@email, @team_id)";
+    const NUMBERED_SQL: &'static str = "INSERT INTO Person (email, team_id) VALUES ($1, $2)";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[
+        ("email", "&'a str"),
+        ("team_id", "i32"),
+    ];
+    const ROW_TYPE: Option<&'static str> = None;
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for InsertPerson<'a> {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "email",
+                    "&'a str",
+                    <&'a str as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+                (
+                    "team_id",
+                    "i32",
+                    <i32 as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        let _ = columns;
+        Vec::new()
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::DebugSql for InsertPerson<'a> {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["INSERT INTO Person (email, team_id) VALUES (", ", ", ")"],
+            &[0usize, 1usize],
+            &[],
+            &[&self.email, &self.team_id],
+        )
+    }
+}
@@ -0,0 +1,21 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+
+enum PersonError {
+    EmailTaken,
+    NoSuchTeam,
+}
+
+#[derive(Statement)]
+#[statement(
+    sql = "INSERT INTO Person (email, team_id) VALUES (@email, @team_id)",
+    on_constraint(
+        "person_email_key" => PersonError::EmailTaken,
+        "person_team_id_fkey" => PersonError::NoSuchTeam,
+    )
+)]
+struct InsertPerson<'a> {
+    email: &'a str,
+    team_id: i32,
+}
@@ -0,0 +1,377 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+#[statement(sql = "UPDATE Person SET password_hash = @password_hash WHERE id = @id")]
+struct SetPassword<'a> {
+    id: i32,
+    #[param(redact)]
+    password_hash: &'a str,
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Statement for SetPassword<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    type Error = postgres_named_parameters::Error;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "UPDATE Person SET password_hash = $2 WHERE id = $1",
+    );
+    fn execute_statement(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<u64, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "SetPassword",
+                method: "execute_statement",
+                sql: "UPDATE Person SET password_hash = $2 WHERE id = $1",
+            },
+            |rows_affected: &u64| *rows_affected,
+            || postgres_named_parameters::internal::error_for_derive_macro::run_redacted(
+                "SetPassword",
+                "UPDATE Person SET password_hash = $2 WHERE id = $1",
+                || {
+                    Ok(
+                        connection
+                            .execute(
+                                "UPDATE Person SET password_hash = $2 WHERE id = $1",
+                                &[&self.id, &self.password_hash],
+                            )?,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Explain for SetPassword<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "SetPassword",
+                method: "explain",
+                sql: "UPDATE Person SET password_hash = $2 WHERE id = $1",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run_redacted(
+                "SetPassword",
+                "UPDATE Person SET password_hash = $2 WHERE id = $1",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "UPDATE Person SET password_hash = $2 WHERE id = $1",
+                        &[&self.id, &self.password_hash],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::QueryMeta for SetPassword<'a> {
+    const NAME: &'static str = "SetPassword";
+    const ORIGINAL_SQL: &'static str = "UPDATE Person SET password_hash = @password_hash WHERE id = @id";
+    const NUMBERED_SQL: &'static str = "UPDATE Person SET password_hash = $2 WHERE id = $1";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[
+        ("id", "i32"),
+        ("password_hash", "&'a str"),
+    ];
+    const ROW_TYPE: Option<&'static str> = None;
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for SetPassword<'a> {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "id",
+                    "i32",
+                    <i32 as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+                (
+                    "password_hash",
+                    "&'a str",
+                    <&'a str as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        let _ = columns;
+        Vec::new()
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::DebugSql for SetPassword<'a> {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["UPDATE Person SET password_hash = ", " WHERE id = ", ""],
+            &[1usize, 0usize],
+            &[1usize],
+            &[&self.id, &self.password_hash],
+        )
+    }
+}
+#[query(
+    sql = "SELECT id FROM Session WHERE token = @token AND user_id = @user_id",
+    row = (i32),
+    redact
+)]
+struct GetSession<'a> {
+    token: &'a str,
+    #[param(redact = false)]
+    user_id: i32,
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Query for GetSession<'a> {
+    type Row = (i32,);
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    const READ_ONLY: bool = false;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT id FROM Session WHERE token = $1 AND user_id = $2",
+    );
+    const EXISTS_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT EXISTS (SELECT id FROM Session WHERE token = $1 AND user_id = $2\n)",
+    );
+    const COUNT_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT count(*) FROM (SELECT id FROM Session WHERE token = $1 AND user_id = $2\n) _",
+    );
+    fn query_all(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Vec<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetSession",
+                method: "query_all",
+                sql: "SELECT id FROM Session WHERE token = $1 AND user_id = $2",
+            },
+            |rows: &Vec<Self::Row>| rows.len() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run_redacted(
+                "GetSession",
+                "SELECT id FROM Session WHERE token = $1 AND user_id = $2",
+                || {
+                    let rows = connection
+                        .query(
+                            "SELECT id FROM Session WHERE token = $1 AND user_id = $2",
+                            &[&self.token, &self.user_id],
+                        )?;
+                    rows.iter()
+                        .map(
+                            postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<
+                                Self::Row,
+                                _,
+                            >,
+                        )
+                        .collect()
+                },
+            ),
+        )
+    }
+    fn query_opt(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Option<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetSession",
+                method: "query_opt",
+                sql: "SELECT id FROM Session WHERE token = $1 AND user_id = $2",
+            },
+            |row: &Option<Self::Row>| row.is_some() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run_redacted(
+                "GetSession",
+                "SELECT id FROM Session WHERE token = $1 AND user_id = $2",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_opt::<
+                        Self::Row,
+                        _,
+                    >(
+                        connection,
+                        "SELECT id FROM Session WHERE token = $1 AND user_id = $2",
+                        &[&self.token, &self.user_id],
+                    )
+                },
+            ),
+        )
+    }
+    fn query_one(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Self::Row, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetSession",
+                method: "query_one",
+                sql: "SELECT id FROM Session WHERE token = $1 AND user_id = $2",
+            },
+            |_: &Self::Row| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run_redacted(
+                "GetSession",
+                "SELECT id FROM Session WHERE token = $1 AND user_id = $2",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        Self::Row,
+                        _,
+                    >(
+                        connection,
+                        "SELECT id FROM Session WHERE token = $1 AND user_id = $2",
+                        &[&self.token, &self.user_id],
+                    )
+                },
+            ),
+        )
+    }
+    fn query_exists(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<bool, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetSession",
+                method: "query_exists",
+                sql: "SELECT EXISTS (SELECT id FROM Session WHERE token = $1 AND user_id = $2\n)",
+            },
+            |_: &bool| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run_redacted(
+                "GetSession",
+                "SELECT EXISTS (SELECT id FROM Session WHERE token = $1 AND user_id = $2\n)",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        bool,
+                        _,
+                    >(
+                        connection,
+                        "SELECT EXISTS (SELECT id FROM Session WHERE token = $1 AND user_id = $2\n)",
+                        &[&self.token, &self.user_id],
+                    )
+                },
+            ),
+        )
+    }
+    fn query_count(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<i64, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetSession",
+                method: "query_count",
+                sql: "SELECT count(*) FROM (SELECT id FROM Session WHERE token = $1 AND user_id = $2\n) _",
+            },
+            |_: &i64| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run_redacted(
+                "GetSession",
+                "SELECT count(*) FROM (SELECT id FROM Session WHERE token = $1 AND user_id = $2\n) _",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        i64,
+                        _,
+                    >(
+                        connection,
+                        "SELECT count(*) FROM (SELECT id FROM Session WHERE token = $1 AND user_id = $2\n) _",
+                        &[&self.token, &self.user_id],
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Explain for GetSession<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "GetSession",
+                method: "explain",
+                sql: "SELECT id FROM Session WHERE token = $1 AND user_id = $2",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run_redacted(
+                "GetSession",
+                "SELECT id FROM Session WHERE token = $1 AND user_id = $2",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "SELECT id FROM Session WHERE token = $1 AND user_id = $2",
+                        &[&self.token, &self.user_id],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::QueryMeta for GetSession<'a> {
+    const NAME: &'static str = "GetSession";
+    const ORIGINAL_SQL: &'static str = "SELECT id FROM Session WHERE token = @token AND user_id = @user_id";
+    const NUMBERED_SQL: &'static str = "SELECT id FROM Session WHERE token = $1 AND user_id = $2";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[
+        ("token", "&'a str"),
+        ("user_id", "i32"),
+    ];
+    const ROW_TYPE: Option<&'static str> = Some("(i32,)");
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for GetSession<'a> {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "token",
+                    "&'a str",
+                    <&'a str as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+                (
+                    "user_id",
+                    "i32",
+                    <i32 as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        use postgres_named_parameters::internal::registry_for_derive_macro::{
+            CheckDecodeRow as _, CheckRowColumns as _,
+        };
+        (&&postgres_named_parameters::internal::registry_for_derive_macro::ColumnChecker::<
+            (i32,),
+        >::new())
+            .check_columns(columns)
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::DebugSql for GetSession<'a> {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["SELECT id FROM Session WHERE token = ", " AND user_id = ", ""],
+            &[0usize, 1usize],
+            &[0usize],
+            &[&self.token, &self.user_id],
+        )
+    }
+}
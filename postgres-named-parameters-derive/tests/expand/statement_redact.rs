@@ -0,0 +1,19 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+
+#[derive(Statement)]
+#[statement(sql = "UPDATE Person SET password_hash = @password_hash WHERE id = @id")]
+struct SetPassword<'a> {
+    id: i32,
+    #[param(redact)]
+    password_hash: &'a str,
+}
+
+#[derive(Query)]
+#[query(sql = "SELECT id FROM Session WHERE token = @token AND user_id = @user_id", row = (i32,), redact)]
+struct GetSession<'a> {
+    token: &'a str,
+    #[param(redact = false)]
+    user_id: i32,
+}
@@ -0,0 +1,223 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+extern crate postgres_from_row;
+use postgres_from_row::FromRow;
+use postgres_named_parameters::*;
+struct InsertedPerson {
+    id: i32,
+}
+impl postgres_from_row::FromRow for InsertedPerson
+where
+    i32: for<'a> postgres_from_row::tokio_postgres::types::FromSql<'a>,
+{
+    fn from_row(row: &postgres_from_row::tokio_postgres::Row) -> Self {
+        Self {
+            id: postgres_from_row::tokio_postgres::Row::get::<&str, i32>(row, "id"),
+        }
+    }
+    fn try_from_row(
+        row: &postgres_from_row::tokio_postgres::Row,
+    ) -> std::result::Result<Self, postgres_from_row::tokio_postgres::Error> {
+        Ok(Self {
+            id: postgres_from_row::tokio_postgres::Row::try_get::<&str, i32>(row, "id")?,
+        })
+    }
+}
+#[statement(
+    sql = "INSERT INTO Person (first_name, last_name) VALUES (@first_name, @last_name) RETURNING id",
+    returning = InsertedPerson
+)]
+struct InsertPerson<'a> {
+    first_name: &'a str,
+    last_name: &'a str,
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Statement for InsertPerson<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    type Error = postgres_named_parameters::Error;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+    );
+    fn execute_statement(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<u64, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPerson",
+                method: "execute_statement",
+                sql: "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+            },
+            |rows_affected: &u64| *rows_affected,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPerson",
+                "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                || {
+                    Ok(
+                        connection
+                            .execute(
+                                "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                                &[&self.first_name, &self.last_name],
+                            )?,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Explain for InsertPerson<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPerson",
+                method: "explain",
+                sql: "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPerson",
+                "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                        &[&self.first_name, &self.last_name],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::QueryMeta for InsertPerson<'a> {
+    const NAME: &'static str = "InsertPerson";
+    const ORIGINAL_SQL: &'static str = "INSERT INTO Person (first_name, last_name) VALUES (@first_name, @last_name) RETURNING id";
+    const NUMBERED_SQL: &'static str = "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[
+        ("first_name", "&'a str"),
+        ("last_name", "&'a str"),
+    ];
+    const ROW_TYPE: Option<&'static str> = Some("InsertedPerson");
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for InsertPerson<'a> {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "first_name",
+                    "&'a str",
+                    <&'a str as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+                (
+                    "last_name",
+                    "&'a str",
+                    <&'a str as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        use postgres_named_parameters::internal::registry_for_derive_macro::{
+            CheckDecodeRow as _, CheckRowColumns as _,
+        };
+        (&&postgres_named_parameters::internal::registry_for_derive_macro::ColumnChecker::<
+            InsertedPerson,
+        >::new())
+            .check_columns(columns)
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::DebugSql for InsertPerson<'a> {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &[
+                "INSERT INTO Person (first_name, last_name) VALUES (",
+                ", ",
+                ") RETURNING id",
+            ],
+            &[0usize, 1usize],
+            &[],
+            &[&self.first_name, &self.last_name],
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::ReturningStatement for InsertPerson<'a> {
+    type Row = InsertedPerson;
+    fn execute_returning_all(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<postgres_named_parameters::Returned<Self::Row>, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPerson",
+                method: "execute_returning_all",
+                sql: "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+            },
+            |returned: &postgres_named_parameters::Returned<Self::Row>| {
+                returned.rows_affected
+            },
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPerson",
+                "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                || {
+                    let parameters: &[&(dyn postgres_named_parameters::postgres::types::ToSql + Sync)] = &[
+                        &self.first_name,
+                        &self.last_name,
+                    ];
+                    let row_iter = connection
+                        .query_raw(
+                            "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                            parameters.iter().copied(),
+                        )?;
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row_iter::<
+                        Self::Row,
+                        _,
+                    >(row_iter)
+                },
+            ),
+        )
+    }
+    fn execute_returning_one(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Self::Row, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPerson",
+                method: "execute_returning_one",
+                sql: "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+            },
+            |_: &Self::Row| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPerson",
+                "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::execute_returning_one::<
+                        Self::Row,
+                        _,
+                    >(
+                        connection,
+                        "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                        &[&self.first_name, &self.last_name],
+                    )
+                },
+            ),
+        )
+    }
+}
@@ -0,0 +1,20 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+extern crate postgres_from_row;
+use postgres_from_row::FromRow;
+use postgres_named_parameters::*;
+
+#[derive(FromRow)]
+struct InsertedPerson {
+    id: i32,
+}
+
+#[derive(Statement)]
+#[statement(
+    sql = "INSERT INTO Person (first_name, last_name) VALUES (@first_name, @last_name) RETURNING id",
+    returning = InsertedPerson
+)]
+struct InsertPerson<'a> {
+    first_name: &'a str,
+    last_name: &'a str,
+}
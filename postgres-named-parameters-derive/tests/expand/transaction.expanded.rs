@@ -0,0 +1,570 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+#[statement(
+    sql = "INSERT INTO Person (first_name, last_name) VALUES (@first_name, @last_name) RETURNING id",
+    returning = i32
+)]
+struct InsertPerson<'a> {
+    first_name: &'a str,
+    last_name: &'a str,
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Statement for InsertPerson<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    type Error = postgres_named_parameters::Error;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+    );
+    fn execute_statement(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<u64, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPerson",
+                method: "execute_statement",
+                sql: "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+            },
+            |rows_affected: &u64| *rows_affected,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPerson",
+                "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                || {
+                    Ok(
+                        connection
+                            .execute(
+                                "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                                &[&self.first_name, &self.last_name],
+                            )?,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Explain for InsertPerson<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPerson",
+                method: "explain",
+                sql: "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPerson",
+                "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                        &[&self.first_name, &self.last_name],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::QueryMeta for InsertPerson<'a> {
+    const NAME: &'static str = "InsertPerson";
+    const ORIGINAL_SQL: &'static str = "INSERT INTO Person (first_name, last_name) VALUES (@first_name, @last_name) RETURNING id";
+    const NUMBERED_SQL: &'static str = "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[
+        ("first_name", "&'a str"),
+        ("last_name", "&'a str"),
+    ];
+    const ROW_TYPE: Option<&'static str> = Some("i32");
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for InsertPerson<'a> {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "first_name",
+                    "&'a str",
+                    <&'a str as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+                (
+                    "last_name",
+                    "&'a str",
+                    <&'a str as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        use postgres_named_parameters::internal::registry_for_derive_macro::{
+            CheckDecodeRow as _, CheckRowColumns as _,
+        };
+        (&&postgres_named_parameters::internal::registry_for_derive_macro::ColumnChecker::<
+            i32,
+        >::new())
+            .check_columns(columns)
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::DebugSql for InsertPerson<'a> {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &[
+                "INSERT INTO Person (first_name, last_name) VALUES (",
+                ", ",
+                ") RETURNING id",
+            ],
+            &[0usize, 1usize],
+            &[],
+            &[&self.first_name, &self.last_name],
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::ReturningStatement for InsertPerson<'a> {
+    type Row = i32;
+    fn execute_returning_all(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<postgres_named_parameters::Returned<Self::Row>, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPerson",
+                method: "execute_returning_all",
+                sql: "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+            },
+            |returned: &postgres_named_parameters::Returned<Self::Row>| {
+                returned.rows_affected
+            },
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPerson",
+                "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                || {
+                    let parameters: &[&(dyn postgres_named_parameters::postgres::types::ToSql + Sync)] = &[
+                        &self.first_name,
+                        &self.last_name,
+                    ];
+                    let row_iter = connection
+                        .query_raw(
+                            "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                            parameters.iter().copied(),
+                        )?;
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row_iter::<
+                        Self::Row,
+                        _,
+                    >(row_iter)
+                },
+            ),
+        )
+    }
+    fn execute_returning_one(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Self::Row, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPerson",
+                method: "execute_returning_one",
+                sql: "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+            },
+            |_: &Self::Row| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPerson",
+                "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::execute_returning_one::<
+                        Self::Row,
+                        _,
+                    >(
+                        connection,
+                        "INSERT INTO Person (first_name, last_name) VALUES ($1, $2) RETURNING id",
+                        &[&self.first_name, &self.last_name],
+                    )
+                },
+            ),
+        )
+    }
+}
+#[query(sql = "SELECT count(*) FROM Person", row = i64)]
+struct CountPeople;
+#[automatically_derived]
+impl postgres_named_parameters::Query for CountPeople {
+    type Row = i64;
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    const READ_ONLY: bool = false;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT count(*) FROM Person",
+    );
+    const EXISTS_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT EXISTS (SELECT count(*) FROM Person\n)",
+    );
+    const COUNT_FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "SELECT count(*) FROM (SELECT count(*) FROM Person\n) _",
+    );
+    fn query_all(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Vec<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "CountPeople",
+                method: "query_all",
+                sql: "SELECT count(*) FROM Person",
+            },
+            |rows: &Vec<Self::Row>| rows.len() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "CountPeople",
+                "SELECT count(*) FROM Person",
+                || {
+                    let rows = connection.query("SELECT count(*) FROM Person", &[])?;
+                    rows.iter()
+                        .map(
+                            postgres_named_parameters::internal::wrapper_for_derive_macro::try_from_row::<
+                                Self::Row,
+                                _,
+                            >,
+                        )
+                        .collect()
+                },
+            ),
+        )
+    }
+    fn query_opt(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Option<Self::Row>, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "CountPeople",
+                method: "query_opt",
+                sql: "SELECT count(*) FROM Person",
+            },
+            |row: &Option<Self::Row>| row.is_some() as u64,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "CountPeople",
+                "SELECT count(*) FROM Person",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_opt::<
+                        Self::Row,
+                        _,
+                    >(connection, "SELECT count(*) FROM Person", &[])
+                },
+            ),
+        )
+    }
+    fn query_one(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<Self::Row, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "CountPeople",
+                method: "query_one",
+                sql: "SELECT count(*) FROM Person",
+            },
+            |_: &Self::Row| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "CountPeople",
+                "SELECT count(*) FROM Person",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        Self::Row,
+                        _,
+                    >(connection, "SELECT count(*) FROM Person", &[])
+                },
+            ),
+        )
+    }
+    fn query_exists(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<bool, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "CountPeople",
+                method: "query_exists",
+                sql: "SELECT EXISTS (SELECT count(*) FROM Person\n)",
+            },
+            |_: &bool| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "CountPeople",
+                "SELECT EXISTS (SELECT count(*) FROM Person\n)",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        bool,
+                        _,
+                    >(connection, "SELECT EXISTS (SELECT count(*) FROM Person\n)", &[])
+                },
+            ),
+        )
+    }
+    fn query_count(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<i64, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "CountPeople",
+                method: "query_count",
+                sql: "SELECT count(*) FROM (SELECT count(*) FROM Person\n) _",
+            },
+            |_: &i64| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "CountPeople",
+                "SELECT count(*) FROM (SELECT count(*) FROM Person\n) _",
+                || {
+                    postgres_named_parameters::internal::wrapper_for_derive_macro::query_one::<
+                        i64,
+                        _,
+                    >(
+                        connection,
+                        "SELECT count(*) FROM (SELECT count(*) FROM Person\n) _",
+                        &[],
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::Explain for CountPeople {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "CountPeople",
+                method: "explain",
+                sql: "SELECT count(*) FROM Person",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "CountPeople",
+                "SELECT count(*) FROM Person",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "SELECT count(*) FROM Person",
+                        &[],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::QueryMeta for CountPeople {
+    const NAME: &'static str = "CountPeople";
+    const ORIGINAL_SQL: &'static str = "SELECT count(*) FROM Person";
+    const NUMBERED_SQL: &'static str = "SELECT count(*) FROM Person";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[];
+    const ROW_TYPE: Option<&'static str> = Some("i64");
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for CountPeople {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        use postgres_named_parameters::internal::registry_for_derive_macro::{
+            CheckDecodeRow as _, CheckRowColumns as _,
+        };
+        (&&postgres_named_parameters::internal::registry_for_derive_macro::ColumnChecker::<
+            i64,
+        >::new())
+            .check_columns(columns)
+    }
+}
+#[automatically_derived]
+impl postgres_named_parameters::DebugSql for CountPeople {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["SELECT count(*) FROM Person"],
+            &[],
+            &[],
+            &[],
+        )
+    }
+}
+#[statement(sql = "INSERT INTO Audit (message) VALUES (@message)")]
+struct InsertAudit<'a> {
+    message: &'a str,
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Statement for InsertAudit<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    type Error = postgres_named_parameters::Error;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "INSERT INTO Audit (message) VALUES ($1)",
+    );
+    fn execute_statement(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<u64, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertAudit",
+                method: "execute_statement",
+                sql: "INSERT INTO Audit (message) VALUES ($1)",
+            },
+            |rows_affected: &u64| *rows_affected,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertAudit",
+                "INSERT INTO Audit (message) VALUES ($1)",
+                || {
+                    Ok(
+                        connection
+                            .execute(
+                                "INSERT INTO Audit (message) VALUES ($1)",
+                                &[&self.message],
+                            )?,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Explain for InsertAudit<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertAudit",
+                method: "explain",
+                sql: "INSERT INTO Audit (message) VALUES ($1)",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertAudit",
+                "INSERT INTO Audit (message) VALUES ($1)",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "INSERT INTO Audit (message) VALUES ($1)",
+                        &[&self.message],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::QueryMeta for InsertAudit<'a> {
+    const NAME: &'static str = "InsertAudit";
+    const ORIGINAL_SQL: &'static str = "INSERT INTO Audit (message) VALUES (@message)";
+    const NUMBERED_SQL: &'static str = "INSERT INTO Audit (message) VALUES ($1)";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("message", "&'a str")];
+    const ROW_TYPE: Option<&'static str> = None;
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for InsertAudit<'a> {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "message",
+                    "&'a str",
+                    <&'a str as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        let _ = columns;
+        Vec::new()
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::DebugSql for InsertAudit<'a> {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["INSERT INTO Audit (message) VALUES (", ")"],
+            &[0usize],
+            &[],
+            &[&self.message],
+        )
+    }
+}
+struct AddPerson<'a> {
+    #[transaction(execute_returning_one)]
+    person: InsertPerson<'a>,
+    #[transaction(query_one)]
+    count: &'a CountPeople,
+    audit: InsertAudit<'a>,
+}
+///The results of running each step of [AddPerson] as a transaction.
+struct AddPersonOutput {
+    ///The result of running `person` using `execute_returning_one`.
+    pub person: <InsertPerson<
+        'static,
+    > as postgres_named_parameters::ReturningStatement>::Row,
+    ///The result of running `count` using `query_one`.
+    pub count: <CountPeople as postgres_named_parameters::Query>::Row,
+    ///The result of running `audit` using `execute_statement`.
+    pub audit: u64,
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Transaction for AddPerson<'a> {
+    type Output = AddPersonOutput;
+    type Error = postgres_named_parameters::Error;
+    fn execute_transaction(
+        &self,
+        connection: &mut impl postgres_named_parameters::postgres::GenericClient,
+    ) -> Result<Self::Output, Self::Error> {
+        use postgres_named_parameters::{
+            Query as _, ReturningStatement as _, Statement as _,
+        };
+        let mut transaction = connection
+            .transaction()
+            .map_err(postgres_named_parameters::Error::from)?;
+        let step_0 = self.person.execute_returning_one(&mut transaction)?;
+        let step_1 = self.count.query_one(&mut transaction)?;
+        let step_2 = self.audit.execute_statement(&mut transaction)?;
+        transaction.commit().map_err(postgres_named_parameters::Error::from)?;
+        Ok(AddPersonOutput {
+            person: step_0,
+            count: step_1,
+            audit: step_2,
+        })
+    }
+}
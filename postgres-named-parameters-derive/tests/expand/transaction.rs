@@ -0,0 +1,32 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+
+#[derive(Statement)]
+#[statement(
+    sql = "INSERT INTO Person (first_name, last_name) VALUES (@first_name, @last_name) RETURNING id",
+    returning = i32
+)]
+struct InsertPerson<'a> {
+    first_name: &'a str,
+    last_name: &'a str,
+}
+
+#[derive(Query)]
+#[query(sql = "SELECT count(*) FROM Person", row = i64)]
+struct CountPeople;
+
+#[derive(Statement)]
+#[statement(sql = "INSERT INTO Audit (message) VALUES (@message)")]
+struct InsertAudit<'a> {
+    message: &'a str,
+}
+
+#[derive(Transaction)]
+struct AddPerson<'a> {
+    #[transaction(execute_returning_one)]
+    person: InsertPerson<'a>,
+    #[transaction(query_one)]
+    count: &'a CountPeople,
+    audit: InsertAudit<'a>,
+}
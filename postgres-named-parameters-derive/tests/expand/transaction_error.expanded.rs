@@ -0,0 +1,280 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+enum PersonError {
+    EmailTaken,
+}
+#[statement(
+    sql = "INSERT INTO Person (email) VALUES (@email)",
+    on_constraint("person_email_key" = >PersonError::EmailTaken)
+)]
+struct InsertPerson<'a> {
+    email: &'a str,
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Statement for InsertPerson<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    type Error = postgres_named_parameters::StatementError<PersonError>;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "INSERT INTO Person (email) VALUES ($1)",
+    );
+    fn execute_statement(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<u64, Self::Error> {
+        (::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPerson",
+                method: "execute_statement",
+                sql: "INSERT INTO Person (email) VALUES ($1)",
+            },
+            |rows_affected: &u64| *rows_affected,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPerson",
+                "INSERT INTO Person (email) VALUES ($1)",
+                || {
+                    Ok(
+                        connection
+                            .execute(
+                                "INSERT INTO Person (email) VALUES ($1)",
+                                &[&self.email],
+                            )?,
+                    )
+                },
+            ),
+        ))
+            .map_err(|error| {
+                let violation = match postgres_named_parameters::internal::constraint_for_derive_macro::violated_constraint(
+                    &error,
+                ) {
+                    Some("person_email_key") => Some(PersonError::EmailTaken),
+                    _ => None,
+                };
+                postgres_named_parameters::internal::constraint_for_derive_macro::statement_error(
+                    error,
+                    violation,
+                )
+            })
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Explain for InsertPerson<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertPerson",
+                method: "explain",
+                sql: "INSERT INTO Person (email) VALUES ($1)",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertPerson",
+                "INSERT INTO Person (email) VALUES ($1)",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "INSERT INTO Person (email) VALUES ($1)",
+                        &[&self.email],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::QueryMeta for InsertPerson<'a> {
+    const NAME: &'static str = "InsertPerson";
+    const ORIGINAL_SQL: &'static str = "INSERT INTO Person (email) VALUES (@email)";
+    const NUMBERED_SQL: &'static str = "INSERT INTO Person (email) VALUES ($1)";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("email", "&'a str")];
+    const ROW_TYPE: Option<&'static str> = None;
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for InsertPerson<'a> {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "email",
+                    "&'a str",
+                    <&'a str as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        let _ = columns;
+        Vec::new()
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::DebugSql for InsertPerson<'a> {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["INSERT INTO Person (email) VALUES (", ")"],
+            &[0usize],
+            &[],
+            &[&self.email],
+        )
+    }
+}
+#[statement(sql = "INSERT INTO Audit (message) VALUES (@message)")]
+struct InsertAudit<'a> {
+    message: &'a str,
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Statement for InsertAudit<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    type Error = postgres_named_parameters::Error;
+    const FINGERPRINT: u64 = postgres_named_parameters::sql_fingerprint(
+        "INSERT INTO Audit (message) VALUES ($1)",
+    );
+    fn execute_statement(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+    ) -> Result<u64, Self::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertAudit",
+                method: "execute_statement",
+                sql: "INSERT INTO Audit (message) VALUES ($1)",
+            },
+            |rows_affected: &u64| *rows_affected,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertAudit",
+                "INSERT INTO Audit (message) VALUES ($1)",
+                || {
+                    Ok(
+                        connection
+                            .execute(
+                                "INSERT INTO Audit (message) VALUES ($1)",
+                                &[&self.message],
+                            )?,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Explain for InsertAudit<'a> {
+    type Requires = postgres_named_parameters::connection::AnyConnection;
+    fn explain(
+        &self,
+        connection: &mut impl postgres_named_parameters::Connection<Self::Requires>,
+        options: postgres_named_parameters::ExplainOptions,
+    ) -> Result<postgres_named_parameters::Plan, postgres_named_parameters::Error> {
+        ::postgres_named_parameters::internal::observer_for_derive_macro::observe(
+            ::postgres_named_parameters::QueryInfo {
+                name: "InsertAudit",
+                method: "explain",
+                sql: "INSERT INTO Audit (message) VALUES ($1)",
+            },
+            |_: &postgres_named_parameters::Plan| 1,
+            || postgres_named_parameters::internal::error_for_derive_macro::run(
+                "InsertAudit",
+                "INSERT INTO Audit (message) VALUES ($1)",
+                || {
+                    postgres_named_parameters::internal::explain_for_derive_macro::explain(
+                        connection,
+                        "INSERT INTO Audit (message) VALUES ($1)",
+                        &[&self.message],
+                        options,
+                    )
+                },
+            ),
+        )
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::QueryMeta for InsertAudit<'a> {
+    const NAME: &'static str = "InsertAudit";
+    const ORIGINAL_SQL: &'static str = "INSERT INTO Audit (message) VALUES (@message)";
+    const NUMBERED_SQL: &'static str = "INSERT INTO Audit (message) VALUES ($1)";
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("message", "&'a str")];
+    const ROW_TYPE: Option<&'static str> = None;
+    const LOCATION: &'static std::panic::Location<'static> = std::panic::Location::caller();
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::internal::registry_for_derive_macro::Validate
+for InsertAudit<'a> {
+    fn check_parameters(
+        types: &[postgres_named_parameters::postgres::types::Type],
+    ) -> Vec<String> {
+        postgres_named_parameters::internal::registry_for_derive_macro::check_parameters(
+            types,
+            &[
+                (
+                    "message",
+                    "&'a str",
+                    <&'a str as postgres_named_parameters::postgres::types::ToSql>::accepts,
+                ),
+            ],
+        )
+    }
+    fn check_columns(
+        columns: &[postgres_named_parameters::postgres::Column],
+    ) -> Vec<String> {
+        let _ = columns;
+        Vec::new()
+    }
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::DebugSql for InsertAudit<'a> {
+    fn to_debug_sql(&self) -> String {
+        postgres_named_parameters::internal::debug_sql_for_derive_macro::to_debug_sql(
+            &["INSERT INTO Audit (message) VALUES (", ")"],
+            &[0usize],
+            &[],
+            &[&self.message],
+        )
+    }
+}
+#[transaction(error = StatementError<PersonError>)]
+struct AddPerson<'a> {
+    person: InsertPerson<'a>,
+    audit: InsertAudit<'a>,
+}
+///The results of running each step of [AddPerson] as a transaction.
+struct AddPersonOutput {
+    ///The result of running `person` using `execute_statement`.
+    pub person: u64,
+    ///The result of running `audit` using `execute_statement`.
+    pub audit: u64,
+}
+#[automatically_derived]
+impl<'a> postgres_named_parameters::Transaction for AddPerson<'a> {
+    type Output = AddPersonOutput;
+    type Error = StatementError<PersonError>;
+    fn execute_transaction(
+        &self,
+        connection: &mut impl postgres_named_parameters::postgres::GenericClient,
+    ) -> Result<Self::Output, Self::Error> {
+        use postgres_named_parameters::{
+            Query as _, ReturningStatement as _, Statement as _,
+        };
+        let mut transaction = connection
+            .transaction()
+            .map_err(postgres_named_parameters::Error::from)?;
+        let step_0 = self.person.execute_statement(&mut transaction)?;
+        let step_1 = self.audit.execute_statement(&mut transaction)?;
+        transaction.commit().map_err(postgres_named_parameters::Error::from)?;
+        Ok(AddPersonOutput {
+            person: step_0,
+            audit: step_1,
+        })
+    }
+}
@@ -0,0 +1,29 @@
+#[macro_use]
+extern crate postgres_named_parameters_derive;
+use postgres_named_parameters::*;
+
+enum PersonError {
+    EmailTaken,
+}
+
+#[derive(Statement)]
+#[statement(
+    sql = "INSERT INTO Person (email) VALUES (@email)",
+    on_constraint("person_email_key" => PersonError::EmailTaken)
+)]
+struct InsertPerson<'a> {
+    email: &'a str,
+}
+
+#[derive(Statement)]
+#[statement(sql = "INSERT INTO Audit (message) VALUES (@message)")]
+struct InsertAudit<'a> {
+    message: &'a str,
+}
+
+#[derive(Transaction)]
+#[transaction(error = StatementError<PersonError>)]
+struct AddPerson<'a> {
+    person: InsertPerson<'a>,
+    audit: InsertAudit<'a>,
+}
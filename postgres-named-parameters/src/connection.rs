@@ -0,0 +1,72 @@
+//! Restricting which kinds of connection a [Statement](crate::Statement) or
+//! [Query](crate::Query) can be run on. See [Connection] for details.
+
+/// A database connection or transaction that a [Statement](crate::Statement)
+/// or [Query](crate::Query) can be run on.
+///
+/// Each statement and query specifies what it `Requires` of the connection:
+/// * [AnyConnection]: it can be run on any connection (i.e.
+///   [postgres::Client]) or transaction (i.e. [postgres::Transaction]). This
+///   is the default.
+/// * [InTransaction]: it can only be run on a transaction (i.e.
+///   [postgres::Transaction]). This is what you get by providing the
+///   `requires_transaction` parameter to `#[statement(...)]` or
+///   `#[query(...)]`.
+///
+/// You should not need to implement this trait yourself, as it is already
+/// implemented for [postgres::Client] and [postgres::Transaction]. Functions
+/// which accept any [postgres::GenericClient] can run statements and queries
+/// which require [AnyConnection] on it.
+///
+/// # Example
+/// ```compile_fail
+/// # use postgres_from_row::FromRow;
+/// # use postgres_named_parameters::Query;
+/// # #[derive(FromRow, Debug)]
+/// # struct Account {
+/// #     id: i32,
+/// #     balance: i64,
+/// # }
+/// #[derive(Query)]
+/// #[query(
+///     sql = "SELECT * FROM Account WHERE id = @id FOR UPDATE",
+///     row = Account,
+///     // The row lock is released as soon as the transaction ends, so this
+///     // query is only useful inside a transaction
+///     requires_transaction
+/// )]
+/// struct LockAccount {
+///     id: i32,
+/// }
+///
+/// fn main() -> Result<(), postgres_named_parameters::Error> {
+///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///         .unwrap_or("host=localhost user=postgres".to_owned());
+///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
+///
+///     // Compiler error: a Client is not a Connection<InTransaction>
+///     let account = LockAccount { id: 1 }.query_one(&mut db)?;
+///
+///     // This, on the other hand, is fine
+///     let mut transaction = db.transaction()?;
+///     let account = LockAccount { id: 1 }.query_one(&mut transaction)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// # Notes
+/// * This crate only supports the synchronous [postgres] client, so there is
+///   no equivalent for `tokio_postgres::Transaction`.
+pub trait Connection<Requires>: postgres::GenericClient {}
+
+/// Marker for statements and queries which can be run on any connection or
+/// transaction. See [Connection].
+pub enum AnyConnection {}
+
+/// Marker for statements and queries which can only be run on a transaction.
+/// See [Connection].
+pub enum InTransaction {}
+
+impl<C: postgres::GenericClient> Connection<AnyConnection> for C {}
+
+impl Connection<InTransaction> for postgres::Transaction<'_> {}
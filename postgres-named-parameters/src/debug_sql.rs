@@ -0,0 +1,374 @@
+use bytes::BytesMut;
+use postgres::types::{FromSql, IsNull, Kind, ToSql, Type};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A statement or query which can be rendered as SQL with its parameters
+/// inlined as literals, for debugging. This is implemented by
+/// `#[derive(Query)]` and `#[derive(Statement)]`.
+///
+/// # Warning
+/// The rendered SQL is for humans (e.g. to paste a failing query into
+/// `psql`). **Don't execute it from your code**: always run the statement or
+/// query itself, which sends the parameters separately from the SQL.
+///
+/// # Example
+/// ```
+/// # use postgres_named_parameters::{DebugSql, Statement};
+/// #[derive(Statement)]
+/// #[statement(sql = "UPDATE Person SET first_name = @name WHERE id = @id")]
+/// struct RenamePerson<'a> {
+///     id: i32,
+///     name: &'a str,
+/// }
+///
+/// let rename = RenamePerson { id: 7, name: "O'Brien" };
+/// assert_eq!(
+///     rename.to_debug_sql(),
+///     "UPDATE Person SET first_name = 'O''Brien' WHERE id = 7"
+/// );
+/// ```
+pub trait DebugSql {
+    /// Render the SQL with each parameter replaced by a literal of its value.
+    ///
+    /// Booleans, integers, floats, text, `bytea`, timestamps
+    /// ([SystemTime]), JSON (`serde_json::Value`), `NULL` and arrays of these
+    /// are supported. Parameters of any other type are left as `$1`, `$2`,
+    /// etc. Redacted parameters (see `#[param(redact)]`) are rendered as
+    /// `<redacted>`.
+    fn to_debug_sql(&self) -> String;
+}
+
+/// The Postgres types a parameter is tried as, in order. The first type the
+/// parameter's [ToSql] implementation accepts is used to render it.
+const TYPES: &[Type] = &[
+    Type::BOOL,
+    Type::INT2,
+    Type::INT4,
+    Type::INT8,
+    Type::OID,
+    Type::FLOAT4,
+    Type::FLOAT8,
+    Type::TEXT,
+    Type::BYTEA,
+    Type::TIMESTAMPTZ,
+    Type::JSONB,
+    Type::BOOL_ARRAY,
+    Type::INT2_ARRAY,
+    Type::INT4_ARRAY,
+    Type::INT8_ARRAY,
+    Type::OID_ARRAY,
+    Type::FLOAT4_ARRAY,
+    Type::FLOAT8_ARRAY,
+    Type::TEXT_ARRAY,
+    Type::BYTEA_ARRAY,
+    Type::TIMESTAMPTZ_ARRAY,
+    Type::JSONB_ARRAY,
+];
+
+type DecodeResult = Result<String, Box<dyn std::error::Error + Sync + Send>>;
+
+/// Join the SQL fragments between the parameters with a literal of each
+/// parameter. `indices` gives the index in `parameters` of each parameter
+/// between two fragments, and `redacted` the indices of the parameters whose
+/// values mustn't be shown. Used by `#[derive(Query)]` and
+/// `#[derive(Statement)]` to implement [DebugSql].
+pub fn to_debug_sql(
+    fragments: &[&str],
+    indices: &[usize],
+    redacted: &[usize],
+    parameters: &[&(dyn ToSql + Sync)],
+) -> String {
+    let mut sql = fragments[0].to_owned();
+    for (&index, fragment) in indices.iter().zip(&fragments[1..]) {
+        if redacted.contains(&index) {
+            sql.push_str("<redacted>");
+        } else {
+            match literal(parameters[index]) {
+                Some(literal) => sql.push_str(&literal),
+                None => sql.push_str(&format!("${}", index + 1)),
+            }
+        }
+        sql.push_str(fragment);
+    }
+    sql
+}
+
+/// Render a parameter as a literal by serializing it as the first type in
+/// [TYPES] it accepts and decoding it again.
+fn literal(parameter: &(dyn ToSql + Sync)) -> Option<String> {
+    let mut raw = BytesMut::new();
+    for ty in TYPES {
+        raw.clear();
+        match parameter.to_sql_checked(ty, &mut raw) {
+            Ok(IsNull::Yes) => return Some("NULL".to_owned()),
+            Ok(IsNull::No) => return decode(ty, &raw).ok(),
+            Err(_) => continue,
+        }
+    }
+    None
+}
+
+fn decode(ty: &Type, raw: &[u8]) -> DecodeResult {
+    match *ty {
+        Type::BOOL => Ok(bool_literal(FromSql::from_sql(ty, raw)?)),
+        Type::INT2 => Ok(i16::from_sql(ty, raw)?.to_string()),
+        Type::INT4 => Ok(i32::from_sql(ty, raw)?.to_string()),
+        Type::INT8 => Ok(i64::from_sql(ty, raw)?.to_string()),
+        Type::OID => Ok(u32::from_sql(ty, raw)?.to_string()),
+        Type::FLOAT4 => Ok(float_literal(f32::from_sql(ty, raw)?.into())),
+        Type::FLOAT8 => Ok(float_literal(FromSql::from_sql(ty, raw)?)),
+        Type::TEXT => Ok(text_literal(FromSql::from_sql(ty, raw)?)),
+        Type::BYTEA => Ok(bytea_literal(FromSql::from_sql(ty, raw)?)),
+        Type::TIMESTAMPTZ => Ok(timestamp_literal(FromSql::from_sql(ty, raw)?)),
+        Type::JSONB => Ok(json_literal(&FromSql::from_sql(ty, raw)?)),
+        Type::BOOL_ARRAY => array_literal(ty, raw, bool_literal),
+        Type::INT2_ARRAY => array_literal(ty, raw, |value: i16| value.to_string()),
+        Type::INT4_ARRAY => array_literal(ty, raw, |value: i32| value.to_string()),
+        Type::INT8_ARRAY => array_literal(ty, raw, |value: i64| value.to_string()),
+        Type::OID_ARRAY => array_literal(ty, raw, |value: u32| value.to_string()),
+        Type::FLOAT4_ARRAY => array_literal(ty, raw, |value: f32| float_literal(value.into())),
+        Type::FLOAT8_ARRAY => array_literal(ty, raw, float_literal),
+        Type::TEXT_ARRAY => array_literal(ty, raw, text_literal),
+        Type::BYTEA_ARRAY => array_literal(ty, raw, bytea_literal),
+        Type::TIMESTAMPTZ_ARRAY => array_literal(ty, raw, timestamp_literal),
+        Type::JSONB_ARRAY => array_literal(ty, raw, |value: serde_json::Value| json_literal(&value)),
+        _ => Err(format!("can't render a literal of type {}", ty).into()),
+    }
+}
+
+fn array_literal<'a, T: FromSql<'a>>(
+    ty: &Type,
+    raw: &'a [u8],
+    literal: impl Fn(T) -> String,
+) -> DecodeResult {
+    let Kind::Array(member_type) = ty.kind() else {
+        return Err(format!("{} is not an array type", ty).into());
+    };
+    let elements = Vec::<Option<T>>::from_sql(ty, raw)?
+        .into_iter()
+        .map(|element| element.map_or_else(|| "NULL".to_owned(), &literal))
+        .collect::<Vec<_>>();
+    // The cast gives empty arrays (and arrays of NULLs) a type
+    Ok(format!("ARRAY[{}]::{}[]", elements.join(", "), member_type.name()))
+}
+
+fn bool_literal(value: bool) -> String {
+    if value { "TRUE" } else { "FALSE" }.to_owned()
+}
+
+fn float_literal(value: f64) -> String {
+    if value.is_nan() {
+        "'NaN'::float8".to_owned()
+    } else if value.is_infinite() {
+        let sign = if value < 0.0 { "-" } else { "" };
+        format!("'{}Infinity'::float8", sign)
+    } else {
+        // Debug formatting keeps the decimal point (e.g. `1.0`) so that the
+        // literal isn't an integer
+        format!("{:?}", value)
+    }
+}
+
+fn text_literal(value: &str) -> String {
+    // Quotes are escaped by doubling them. Backslashes don't need escaping as
+    // long as standard_conforming_strings is on (the default since 9.1)
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn bytea_literal(value: &[u8]) -> String {
+    let hex: String = value.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("'\\x{}'::bytea", hex)
+}
+
+fn json_literal(value: &serde_json::Value) -> String {
+    format!("{}::jsonb", text_literal(&value.to_string()))
+}
+
+fn timestamp_literal(value: SystemTime) -> String {
+    let microseconds = match value.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_micros() as i128,
+        Err(err) => -(err.duration().as_micros() as i128),
+    };
+    let days = microseconds.div_euclid(86_400_000_000) as i64;
+    let microseconds = microseconds.rem_euclid(86_400_000_000) as i64;
+    let (year, month, day) = civil_from_days(days);
+    // Postgres has no year 0: the year before 1 AD is 1 BC
+    let (year, era) = if year > 0 { (year, "") } else { (1 - year, " BC") };
+    format!(
+        "'{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:06}+00{}'::timestamptz",
+        year,
+        month,
+        day,
+        microseconds / 3_600_000_000,
+        microseconds / 60_000_000 % 60,
+        microseconds / 1_000_000 % 60,
+        microseconds % 1_000_000,
+        era,
+    )
+}
+
+/// Convert a number of days since 1970-01-01 into a (year, month, day) date in
+/// the proleptic Gregorian calendar. See
+/// <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Render a single parameter the way `to_debug_sql` would.
+    fn render(parameter: &(dyn ToSql + Sync)) -> String {
+        to_debug_sql(&["", ""], &[0], &[], &[parameter])
+    }
+
+    fn seconds_from_epoch(seconds: i64) -> SystemTime {
+        if seconds >= 0 {
+            UNIX_EPOCH + Duration::from_secs(seconds as u64)
+        } else {
+            UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+        }
+    }
+
+    #[test]
+    fn bool_literals() {
+        assert_eq!(render(&true), "TRUE");
+        assert_eq!(render(&false), "FALSE");
+    }
+
+    #[test]
+    fn integer_literals() {
+        assert_eq!(render(&-12i16), "-12");
+        assert_eq!(render(&7i32), "7");
+        assert_eq!(render(&i64::MIN), "-9223372036854775808");
+        assert_eq!(render(&4_000_000_000u32), "4000000000");
+    }
+
+    #[test]
+    fn float_literals() {
+        assert_eq!(render(&1.0f64), "1.0");
+        assert_eq!(render(&-0.5f32), "-0.5");
+        assert_eq!(render(&f64::NAN), "'NaN'::float8");
+        assert_eq!(render(&f64::INFINITY), "'Infinity'::float8");
+        assert_eq!(render(&f32::NEG_INFINITY), "'-Infinity'::float8");
+    }
+
+    #[test]
+    fn text_literals() {
+        assert_eq!(render(&"O'Brien"), "'O''Brien'");
+        assert_eq!(render(&String::from(r"C:\path")), r"'C:\path'");
+        assert_eq!(render(&""), "''");
+    }
+
+    #[test]
+    fn bytea_literals() {
+        assert_eq!(render(&vec![0u8, 0xab, 0xff]), r"'\x00abff'::bytea");
+        assert_eq!(render(&Vec::<u8>::new()), r"'\x'::bytea");
+    }
+
+    #[test]
+    fn json_literals() {
+        let value = serde_json::json!({"name": "O'Brien"});
+        assert_eq!(render(&value), r#"'{"name":"O''Brien"}'::jsonb"#);
+    }
+
+    #[test]
+    fn null_literals() {
+        assert_eq!(render(&None::<i32>), "NULL");
+        assert_eq!(render(&None::<&str>), "NULL");
+    }
+
+    #[test]
+    fn timestamp_literals() {
+        assert_eq!(
+            render(&UNIX_EPOCH),
+            "'1970-01-01 00:00:00.000000+00'::timestamptz"
+        );
+        assert_eq!(
+            render(&(seconds_from_epoch(1_700_000_000) + Duration::from_micros(123_456))),
+            "'2023-11-14 22:13:20.123456+00'::timestamptz"
+        );
+        // 2000-02-29 was a leap day
+        assert_eq!(
+            render(&seconds_from_epoch(951_782_400)),
+            "'2000-02-29 00:00:00.000000+00'::timestamptz"
+        );
+    }
+
+    #[test]
+    fn timestamp_literals_before_1970() {
+        assert_eq!(
+            render(&(UNIX_EPOCH - Duration::from_micros(1))),
+            "'1969-12-31 23:59:59.999999+00'::timestamptz"
+        );
+        assert_eq!(
+            render(&seconds_from_epoch(-2_208_988_800)),
+            "'1900-01-01 00:00:00.000000+00'::timestamptz"
+        );
+    }
+
+    #[test]
+    fn timestamp_literals_before_common_era() {
+        // The first instant of 1 AD, and the last of 1 BC (Postgres has no
+        // year 0)
+        assert_eq!(
+            render(&seconds_from_epoch(-62_135_596_800)),
+            "'0001-01-01 00:00:00.000000+00'::timestamptz"
+        );
+        assert_eq!(
+            render(&seconds_from_epoch(-62_135_596_801)),
+            "'0001-12-31 23:59:59.000000+00 BC'::timestamptz"
+        );
+        assert_eq!(
+            render(&seconds_from_epoch(-62_167_219_200)),
+            "'0001-01-01 00:00:00.000000+00 BC'::timestamptz"
+        );
+        assert_eq!(
+            render(&seconds_from_epoch(-63_517_780_800)),
+            "'0044-03-15 12:00:00.000000+00 BC'::timestamptz"
+        );
+    }
+
+    #[test]
+    fn array_literals() {
+        assert_eq!(render(&vec![1i32, 2, 3]), "ARRAY[1, 2, 3]::int4[]");
+        assert_eq!(
+            render(&vec![Some("a'b"), None]),
+            "ARRAY['a''b', NULL]::text[]"
+        );
+        assert_eq!(render(&Vec::<bool>::new()), "ARRAY[]::bool[]");
+        assert_eq!(
+            render(&vec![UNIX_EPOCH]),
+            "ARRAY['1970-01-01 00:00:00.000000+00'::timestamptz]::timestamptz[]"
+        );
+    }
+
+    #[test]
+    fn unsupported_parameters_are_left_numbered() {
+        // i8 is only accepted as Postgres's "char" type
+        assert_eq!(
+            to_debug_sql(&["SELECT ", ", ", ""], &[1, 0], &[], &[&1i32, &1i8]),
+            "SELECT $2, 1"
+        );
+    }
+
+    #[test]
+    fn redacted_parameters() {
+        assert_eq!(
+            to_debug_sql(&["SELECT ", ", ", ""], &[0, 1], &[1], &[&1i32, &"secret"]),
+            "SELECT 1, <redacted>"
+        );
+    }
+}
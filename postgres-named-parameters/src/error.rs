@@ -0,0 +1,513 @@
+//! The error type returned by statements and queries. See [Error].
+use postgres::error::SqlState;
+
+/// An error returned by a [Statement](crate::Statement),
+/// [Query](crate::Query) or any of the other traits in this crate.
+///
+/// Besides the [kind](Error::kind) of failure, the error records which
+/// statement or query failed (i.e. the name of the struct it was derived for)
+/// and the numbered SQL that was run, so that a log line says more than just
+/// "query returned an unexpected number of rows".
+///
+/// # Example
+/// ```no_run
+/// # use postgres_from_row::FromRow;
+/// # use postgres_named_parameters::{ErrorKind, Query};
+/// # #[derive(FromRow, Debug)]
+/// # struct Person {
+/// #     first_name: String,
+/// # }
+/// #[derive(Query)]
+/// #[query(sql = "SELECT * FROM Person WHERE id = @id", row = Person)]
+/// struct GetPerson {
+///     id: i32,
+/// }
+///
+/// fn main() -> Result<(), postgres_named_parameters::Error> {
+///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///         .unwrap_or("host=localhost user=postgres".to_owned());
+///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
+///
+///     match (GetPerson { id: 123 }).query_one(&mut db) {
+///         Ok(person) => println!("Found: {:?}", person),
+///         Err(err) if *err.kind() == ErrorKind::NotFound => println!("No such person"),
+///         Err(err) => {
+///             println!("{} failed: {}\nSQL: {:?}", err.query().unwrap_or("?"), err, err.sql());
+///             return Err(err);
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+///
+/// # Notes
+/// * Every `Error` wraps a [postgres::Error] (see
+///   [as_postgres_error](Error::as_postgres_error)), and it converts from and
+///   into [postgres::Error]. This means you can still use `?` in functions
+///   returning `Result<_, postgres::Error>`, such as the closure passed to
+///   [run_in_transaction](crate::run_in_transaction).
+/// * [NotFound](ErrorKind::NotFound), [TooManyRows](ErrorKind::TooManyRows)
+///   and [RowCountMismatch](ErrorKind::RowCountMismatch) errors are detected
+///   by this crate rather than `postgres`. Since `postgres` gives no way to
+///   create a [postgres::Error], these errors wrap the same "query returned an
+///   unexpected number of rows" error that `postgres` itself returns, which is
+///   obtained by running a query returning no rows through `query_one` once
+///   the wrong number of rows has been found. (If that fails too, e.g.
+///   because the connection was lost, they wrap that error instead.)
+/// * If the statement or query has any redacted parameters (see
+///   [Redacting parameters](crate::Statement#redacting-parameters)), the
+///   `DETAIL` of the database's error (which often contains the values of the
+///   offending row, e.g. `Key (email)=(...) already exists`) is replaced by
+///   `<redacted>` when the error is displayed or debug-formatted, and
+///   [source](std::error::Error::source) returns `None` so that error
+///   reporters such as `anyhow` can't print it either. This holds however the
+///   error is passed up, e.g. using `?` into a `Box<dyn std::error::Error>`.
+///   Only
+///   [as_postgres_error](Error::as_postgres_error) and the conversion into
+///   [postgres::Error] give access to the unredacted [postgres::Error], so
+///   don't log what they return.
+pub struct Error {
+    kind: ErrorKind,
+    query: Option<&'static str>,
+    sql: Option<&'static str>,
+    redacted: bool,
+    source: postgres::Error,
+}
+
+/// The kind of failure described by an [Error].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The database returned an error, or communicating with the database
+    /// failed (in which case `code` is `None`).
+    Db {
+        /// The SQLSTATE code of the error returned by the database.
+        code: Option<SqlState>,
+    },
+    /// A row returned by the database could not be decoded.
+    Decode {
+        /// The name of the column which could not be decoded, if known. It
+        /// is not known for struct rows, since
+        /// [FromRow](postgres_from_row::FromRow) doesn't say which field
+        /// failed (though the error's message does).
+        column: Option<String>,
+    },
+    /// A method expecting exactly one row (e.g.
+    /// [query_one](crate::Query::query_one)) found no rows.
+    NotFound,
+    /// A method expecting at most one row (e.g.
+    /// [query_opt](crate::Query::query_opt)) found more than one row.
+    TooManyRows,
+    /// The statement or query exceeded its `timeout` (i.e. it was cancelled
+    /// while running with the statement timeout set by its `timeout`
+    /// parameter).
+    Timeout,
+    /// The output of `EXPLAIN` (see [Explain](crate::Explain)) could not be
+    /// parsed into a [Plan](crate::Plan).
+    Explain,
+    /// The statement affected a different number of rows than its
+    /// `expect_rows` parameter allows.
+    RowCountMismatch {
+        /// The number of rows the statement was expected to affect.
+        expected: ExpectedRows,
+        /// The number of rows the statement actually affected.
+        actual: u64,
+    },
+}
+
+/// The number of rows a [Statement](crate::Statement) is expected to affect,
+/// as specified by its `expect_rows` parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpectedRows {
+    /// The minimum number of rows.
+    pub min: u64,
+    /// The maximum number of rows, or `None` if there is no maximum.
+    pub max: Option<u64>,
+}
+
+impl ExpectedRows {
+    /// Whether the given number of rows is allowed.
+    pub fn contains(&self, rows: u64) -> bool {
+        rows >= self.min && self.max.is_none_or(|max| rows <= max)
+    }
+}
+
+impl std::fmt::Display for ExpectedRows {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "exactly {}", max),
+            Some(max) if self.min == 0 => write!(f, "at most {}", max),
+            Some(max) => write!(f, "between {} and {}", self.min, max),
+            None => write!(f, "at least {}", self.min),
+        }
+    }
+}
+
+impl Error {
+    /// The kind of failure.
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// The name of the statement or query which failed (i.e. the name of the
+    /// struct it was derived for), if known.
+    pub fn query(&self) -> Option<&str> {
+        self.query
+    }
+
+    /// The numbered SQL which was run, if known.
+    pub fn sql(&self) -> Option<&str> {
+        self.sql
+    }
+
+    /// Whether the statement or query which failed has redacted parameters,
+    /// in which case the `DETAIL` of the database's error is hidden.
+    pub fn is_redacted(&self) -> bool {
+        self.redacted
+    }
+
+    /// The SQLSTATE code of the error returned by the database, if any.
+    pub fn code(&self) -> Option<&SqlState> {
+        self.source.code()
+    }
+
+    /// The underlying [postgres::Error] (see the notes above for errors
+    /// detected by this crate). Its `DETAIL` is not redacted, even if
+    /// [is_redacted](Error::is_redacted) is `true`.
+    pub fn as_postgres_error(&self) -> &postgres::Error {
+        &self.source
+    }
+
+    /// Consume this error and return the underlying [postgres::Error] (see
+    /// [as_postgres_error](Error::as_postgres_error)). This is the same as
+    /// converting it using [From].
+    ///
+    /// This is lossy: the kind, the statement or query which failed and its
+    /// SQL are discarded. The `DETAIL` of the database's error is not
+    /// redacted either.
+    pub fn into_postgres_error(self) -> postgres::Error {
+        self.source
+    }
+
+    /// Create an error for a row which could not be decoded, where `column`
+    /// is the name of the offending column if known.
+    pub(crate) fn decode(source: postgres::Error, column: Option<&str>) -> Error {
+        let column = column.map(str::to_owned);
+        Error::new(ErrorKind::Decode { column }, source)
+    }
+
+    /// Create an error for a statement or query which returned or affected
+    /// the wrong number of rows. postgres::Error has no public constructors,
+    /// so to be able to convert into the same error `query_one` would have
+    /// returned, run a query which returns no rows through `query_one`. This
+    /// only happens once the number of rows was found to be wrong.
+    pub(crate) fn row_count(
+        kind: ErrorKind,
+        connection: &mut impl postgres::GenericClient,
+    ) -> Error {
+        match connection.query_one("SELECT 1 WHERE false", &[]) {
+            Ok(_) => unreachable!("a query with no rows returned a row"),
+            Err(source) if source.code().is_none() => Error::new(kind, source),
+            Err(source) => source.into(),
+        }
+    }
+
+    pub(crate) fn new(kind: ErrorKind, source: postgres::Error) -> Error {
+        Error {
+            kind,
+            query: None,
+            sql: None,
+            redacted: false,
+            source,
+        }
+    }
+
+    /// Classify a database error as a [Timeout](ErrorKind::Timeout). Only
+    /// used for errors returned while a statement timeout is set, where a
+    /// cancelled query means the timeout was exceeded.
+    pub(crate) fn timed_out(mut self) -> Error {
+        if self.kind == (ErrorKind::Db { code: Some(SqlState::QUERY_CANCELED) }) {
+            self.kind = ErrorKind::Timeout;
+        }
+        self
+    }
+
+    /// Record which statement or query failed, unless it was already recorded
+    /// (e.g. by a nested query).
+    pub(crate) fn with_context(mut self, query: &'static str, sql: &'static str) -> Error {
+        if self.query.is_none() {
+            self.query = Some(query);
+            self.sql = Some(sql);
+        }
+        self
+    }
+
+    /// Hide the details of the database's error, since they may contain the
+    /// values of redacted parameters.
+    pub(crate) fn redact(mut self) -> Error {
+        self.redacted = true;
+        self
+    }
+}
+
+impl std::fmt::Debug for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("Error");
+        debug
+            .field("kind", &self.kind)
+            .field("query", &self.query)
+            .field("sql", &self.sql)
+            .field("redacted", &self.redacted);
+        if self.redacted {
+            debug.field("source", &RedactedSource(&self.source));
+        } else {
+            debug.field("source", &self.source);
+        }
+        debug.finish()
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(query) = self.query {
+            write!(f, "{}: ", query)?;
+        }
+        match &self.kind {
+            ErrorKind::NotFound => write!(f, "query returned no rows"),
+            ErrorKind::TooManyRows => write!(f, "query returned more than one row"),
+            ErrorKind::Timeout => write!(f, "statement timeout exceeded"),
+            ErrorKind::Explain => match std::error::Error::source(&self.source) {
+                Some(cause) => write!(f, "error parsing the plan: {}", cause),
+                None => write!(f, "error parsing the plan: {}", self.source),
+            },
+            ErrorKind::RowCountMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "statement affected {} rows, expected {}",
+                    actual, expected
+                )
+            }
+            ErrorKind::Decode {
+                column: Some(column),
+            } => match std::error::Error::source(&self.source) {
+                Some(cause) => write!(f, "error decoding column `{}`: {}", column, cause),
+                None => write!(f, "error decoding column `{}`", column),
+            },
+            // postgres::Error only says "db error", so show the database's
+            // message instead
+            _ => match self.source.as_db_error() {
+                Some(_) if self.redacted => RedactedSource(&self.source).fmt(f),
+                Some(db_error) => write!(f, "{}", db_error),
+                None => write!(f, "{}", self.source),
+            },
+        }
+    }
+}
+
+/// Formats a [postgres::Error] without the `DETAIL` of the database's error,
+/// which may contain the values of redacted parameters.
+struct RedactedSource<'a>(&'a postgres::Error);
+
+impl std::fmt::Display for RedactedSource<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(db_error) = self.0.as_db_error() else {
+            return write!(f, "{}", self.0);
+        };
+        write!(f, "{}: {}", db_error.severity(), db_error.message())?;
+        if db_error.detail().is_some() {
+            write!(f, "\nDETAIL: <redacted>")?;
+        }
+        if let Some(hint) = db_error.hint() {
+            write!(f, "\nHINT: {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for RedactedSource<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.to_string())
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        // The source's own source is the database's error, DETAIL and all
+        if self.redacted {
+            return None;
+        }
+        Some(&self.source)
+    }
+}
+
+impl From<postgres::Error> for Error {
+    fn from(source: postgres::Error) -> Error {
+        let kind = ErrorKind::Db {
+            code: source.code().cloned(),
+        };
+        Error::new(kind, source)
+    }
+}
+
+impl From<Error> for postgres::Error {
+    fn from(error: Error) -> postgres::Error {
+        error.source
+    }
+}
+
+/// The error returned by a [Statement](crate::Statement) which uses the
+/// `on_constraint` parameter, where `E` is your own error type.
+///
+/// If the statement violates one of the constraints given to `on_constraint`,
+/// the error is a [Constraint](StatementError::Constraint) holding the
+/// corresponding value of `E`. Otherwise, it is [Other](StatementError::Other).
+///
+/// # Example
+/// ```no_run
+/// # use postgres_named_parameters::{Statement, StatementError};
+/// #[derive(Debug)]
+/// enum PersonError {
+///     EmailTaken,
+///     NoSuchTeam,
+///     MissingEmail,
+/// }
+///
+/// #[derive(Statement)]
+/// #[statement(
+///     sql = "INSERT INTO Person (email, team_id) VALUES (@email, @team_id)",
+///     on_constraint(
+///         "person_email_key" => PersonError::EmailTaken,
+///         "person_team_id_fkey" => PersonError::NoSuchTeam,
+///         // Not-null violations are matched by column name instead
+///         "email" => PersonError::MissingEmail,
+///     )
+/// )]
+/// struct InsertPerson<'a> {
+///     email: Option<&'a str>,
+///     team_id: i32,
+/// }
+///
+/// fn main() -> Result<(), postgres_named_parameters::Error> {
+///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///         .unwrap_or("host=localhost user=postgres".to_owned());
+///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
+///
+///     let insert = InsertPerson {
+///         email: Some("john@example.com"),
+///         team_id: 1,
+///     };
+///     match insert.execute_statement(&mut db) {
+///         Ok(_) => println!("Inserted"),
+///         Err(StatementError::Constraint {
+///             violation: PersonError::EmailTaken,
+///             ..
+///         }) => println!("Someone already has that email"),
+///         Err(err) => return Err(err.into()),
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub enum StatementError<E> {
+    /// The statement violated one of the constraints given to `on_constraint`.
+    Constraint {
+        /// The value given to `on_constraint` for the violated constraint.
+        violation: E,
+        /// The underlying error.
+        error: Error,
+    },
+    /// Any other error.
+    Other(Error),
+}
+
+impl<E> StatementError<E> {
+    /// The value given to `on_constraint` for the violated constraint, if this
+    /// is a [Constraint](StatementError::Constraint) error.
+    pub fn violation(&self) -> Option<&E> {
+        match self {
+            StatementError::Constraint { violation, .. } => Some(violation),
+            StatementError::Other(_) => None,
+        }
+    }
+
+    /// The underlying error.
+    pub fn error(&self) -> &Error {
+        match self {
+            StatementError::Constraint { error, .. } | StatementError::Other(error) => error,
+        }
+    }
+
+    /// Consume this error and return the underlying error.
+    pub fn into_error(self) -> Error {
+        match self {
+            StatementError::Constraint { error, .. } | StatementError::Other(error) => error,
+        }
+    }
+}
+
+impl<E> std::fmt::Display for StatementError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error().fmt(f)
+    }
+}
+
+impl<E: std::fmt::Debug> std::error::Error for StatementError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.error())
+    }
+}
+
+impl<E> From<Error> for StatementError<E> {
+    fn from(error: Error) -> StatementError<E> {
+        StatementError::Other(error)
+    }
+}
+
+impl<E> From<postgres::Error> for StatementError<E> {
+    fn from(error: postgres::Error) -> StatementError<E> {
+        StatementError::Other(error.into())
+    }
+}
+
+impl<E> From<StatementError<E>> for Error {
+    fn from(error: StatementError<E>) -> Error {
+        error.into_error()
+    }
+}
+
+impl<E> From<StatementError<E>> for postgres::Error {
+    fn from(error: StatementError<E>) -> postgres::Error {
+        error.into_error().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expected_rows_contains_its_bounds() {
+        let exactly_one = ExpectedRows { min: 1, max: Some(1) };
+        assert!(!exactly_one.contains(0));
+        assert!(exactly_one.contains(1));
+        assert!(!exactly_one.contains(2));
+
+        let at_most_one = ExpectedRows { min: 0, max: Some(1) };
+        assert!(at_most_one.contains(0));
+        assert!(at_most_one.contains(1));
+        assert!(!at_most_one.contains(2));
+
+        let at_least_one = ExpectedRows { min: 1, max: None };
+        assert!(!at_least_one.contains(0));
+        assert!(at_least_one.contains(1));
+        assert!(at_least_one.contains(u64::MAX));
+    }
+
+    #[test]
+    fn expected_rows_display() {
+        assert_eq!(ExpectedRows { min: 1, max: Some(1) }.to_string(), "exactly 1");
+        assert_eq!(ExpectedRows { min: 0, max: Some(1) }.to_string(), "at most 1");
+        assert_eq!(ExpectedRows { min: 2, max: Some(4) }.to_string(), "between 2 and 4");
+        assert_eq!(ExpectedRows { min: 1, max: None }.to_string(), "at least 1");
+    }
+}
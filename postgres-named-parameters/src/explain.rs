@@ -0,0 +1,393 @@
+use crate::{Error, ErrorKind};
+use postgres::types::{FromSql, ToSql, Type};
+use serde_json::Value;
+
+/// A statement or query whose execution plan can be shown using Postgres's
+/// `EXPLAIN`. This is implemented by `#[derive(Query)]` and
+/// `#[derive(Statement)]`.
+///
+/// # Example
+/// ```no_run
+/// # use postgres_from_row::FromRow;
+/// # use postgres_named_parameters::{Explain, ExplainOptions, Query};
+/// # #[derive(FromRow, Debug)]
+/// # struct Person {
+/// #     first_name: String,
+/// # }
+/// #[derive(Query)]
+/// #[query(sql = "SELECT * FROM Person WHERE id = @id", row = Person)]
+/// struct GetPerson {
+///     id: i32,
+/// }
+///
+/// fn main() -> Result<(), postgres_named_parameters::Error> {
+///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///         .unwrap_or("host=localhost user=postgres".to_owned());
+///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
+///
+///     let options = ExplainOptions {
+///         analyze: true,
+///         ..Default::default()
+///     };
+///     let plan = GetPerson { id: 123 }.explain(&mut db, options)?;
+///     assert!(plan.index_names().any(|index| index == "person_pkey"));
+///     println!("{}", plan);
+///     Ok(())
+/// }
+/// ```
+pub trait Explain {
+    /// What the statement or query requires of the connection it is run on
+    /// (see [Connection](crate::Connection)). This is the same as the
+    /// `Requires` type of its [Statement](crate::Statement) or
+    /// [Query](crate::Query) implementation.
+    type Requires;
+
+    /// Run `EXPLAIN (FORMAT JSON, ...)` on the statement or query, with the
+    /// same parameters it would normally be run with, and return the parsed
+    /// plan.
+    ///
+    /// This is run inside a transaction (or a savepoint, if you pass a
+    /// transaction) which is always rolled back. This matters when
+    /// [analyze](ExplainOptions::analyze) is set, because `EXPLAIN ANALYZE`
+    /// really runs the statement.
+    fn explain(
+        &self,
+        connection: &mut impl crate::Connection<Self::Requires>,
+        options: ExplainOptions,
+    ) -> Result<Plan, Error>;
+}
+
+/// Options for [Explain::explain].
+///
+/// Use [Default] to fill in the options you don't care about.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExplainOptions {
+    /// Whether to actually run the statement or query (`ANALYZE`), so that the
+    /// plan includes the actual number of rows and timings.
+    pub analyze: bool,
+    /// Whether to include buffer usage (`BUFFERS`). Postgres versions before
+    /// 16 only allow this together with [analyze](ExplainOptions::analyze).
+    pub buffers: bool,
+    /// Whether to include extra information such as each node's output
+    /// columns (`VERBOSE`).
+    pub verbose: bool,
+}
+
+impl ExplainOptions {
+    /// The options to pass to `EXPLAIN`, e.g. `FORMAT JSON, ANALYZE`.
+    fn to_sql(self) -> String {
+        let mut options = vec!["FORMAT JSON"];
+        if self.analyze {
+            options.push("ANALYZE");
+        }
+        if self.buffers {
+            options.push("BUFFERS");
+        }
+        if self.verbose {
+            options.push("VERBOSE");
+        }
+        options.join(", ")
+    }
+}
+
+/// The execution plan of a statement or query, as returned by
+/// [Explain::explain].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    /// The top node of the plan tree.
+    pub root: PlanNode,
+    /// How long planning took, in milliseconds. Only present when
+    /// [analyze](ExplainOptions::analyze) was set.
+    pub planning_time: Option<f64>,
+    /// How long execution took, in milliseconds. Only present when
+    /// [analyze](ExplainOptions::analyze) was set.
+    pub execution_time: Option<f64>,
+}
+
+impl Plan {
+    /// Iterate over every node in the plan, parents before their children.
+    pub fn nodes(&self) -> impl Iterator<Item = &PlanNode> {
+        self.root.nodes()
+    }
+
+    /// Iterate over the names of the indexes used by the plan.
+    pub fn index_names(&self) -> impl Iterator<Item = &str> {
+        self.nodes().filter_map(|node| node.index_name.as_deref())
+    }
+
+    /// The shape of the plan: an indented tree of each node's type, index and
+    /// table, without costs, row counts or timings. Unlike the full plan, this
+    /// only changes when Postgres picks a different plan (e.g. a `Seq Scan`
+    /// instead of an `Index Scan`), so it can be compared between runs. See
+    /// [assert_plan_snapshot](crate::assert_plan_snapshot).
+    pub fn shape(&self) -> String {
+        let mut shape = String::new();
+        self.root
+            .fmt_indented(&mut shape, 0, false)
+            .expect("writing to a String can't fail");
+        shape
+    }
+
+    /// Parse the output of `EXPLAIN (FORMAT JSON)`.
+    fn from_json(json: &Value) -> Option<Plan> {
+        let plan = json.get(0)?;
+        Some(Plan {
+            root: PlanNode::from_json(plan.get("Plan")?)?,
+            planning_time: plan.get("Planning Time").and_then(Value::as_f64),
+            execution_time: plan.get("Execution Time").and_then(Value::as_f64),
+        })
+    }
+}
+
+/// Formats the plan as an indented tree, similar to `EXPLAIN`'s default text
+/// format.
+impl std::fmt::Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.root.fmt_indented(f, 0, true)?;
+        if let Some(planning_time) = self.planning_time {
+            writeln!(f, "Planning Time: {:.3} ms", planning_time)?;
+        }
+        if let Some(execution_time) = self.execution_time {
+            writeln!(f, "Execution Time: {:.3} ms", execution_time)?;
+        }
+        Ok(())
+    }
+}
+
+/// A single node of a [Plan], e.g. a `Seq Scan` or a `Hash Join`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanNode {
+    /// The kind of node, e.g. `Seq Scan`, `Index Scan` or `Hash Join`.
+    pub node_type: String,
+    /// The table scanned by this node, if any.
+    pub relation_name: Option<String>,
+    /// The index used by this node, if any.
+    pub index_name: Option<String>,
+    /// The estimated cost of returning the first row.
+    pub startup_cost: f64,
+    /// The estimated cost of returning all rows.
+    pub total_cost: f64,
+    /// The estimated number of rows returned.
+    pub plan_rows: f64,
+    /// The actual number of rows returned (per loop). Only present when
+    /// [analyze](ExplainOptions::analyze) was set.
+    pub actual_rows: Option<f64>,
+    /// The actual time taken to return all rows (per loop), in milliseconds.
+    /// Only present when [analyze](ExplainOptions::analyze) was set.
+    pub actual_total_time: Option<f64>,
+    /// The number of shared blocks found in the buffer cache. Only present
+    /// when [buffers](ExplainOptions::buffers) was set.
+    pub shared_hit_blocks: Option<u64>,
+    /// The number of shared blocks read from disk. Only present when
+    /// [buffers](ExplainOptions::buffers) was set.
+    pub shared_read_blocks: Option<u64>,
+    /// The nodes this node gets its rows from.
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    /// Iterate over this node and all of its descendants, parents before their
+    /// children.
+    pub fn nodes(&self) -> impl Iterator<Item = &PlanNode> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+
+    fn from_json(json: &Value) -> Option<PlanNode> {
+        let string = |key| json.get(key).and_then(Value::as_str).map(str::to_owned);
+        let children = match json.get("Plans") {
+            Some(Value::Array(children)) => {
+                children.iter().map(PlanNode::from_json).collect::<Option<_>>()?
+            }
+            _ => vec![],
+        };
+        Some(PlanNode {
+            node_type: string("Node Type")?,
+            relation_name: string("Relation Name"),
+            index_name: string("Index Name"),
+            startup_cost: json.get("Startup Cost")?.as_f64()?,
+            total_cost: json.get("Total Cost")?.as_f64()?,
+            plan_rows: json.get("Plan Rows")?.as_f64()?,
+            actual_rows: json.get("Actual Rows").and_then(Value::as_f64),
+            actual_total_time: json.get("Actual Total Time").and_then(Value::as_f64),
+            shared_hit_blocks: json.get("Shared Hit Blocks").and_then(Value::as_u64),
+            shared_read_blocks: json.get("Shared Read Blocks").and_then(Value::as_u64),
+            children,
+        })
+    }
+
+    /// Write this node and its descendants as an indented tree, with or
+    /// without the costs and actual rows.
+    fn fmt_indented(&self, f: &mut impl std::fmt::Write, depth: usize, costs: bool) -> std::fmt::Result {
+        if depth > 0 {
+            write!(f, "{:width$}->  ", "", width = depth * 6 - 4)?;
+        }
+        write!(f, "{}", self.node_type)?;
+        if let Some(index_name) = &self.index_name {
+            write!(f, " using {}", index_name)?;
+        }
+        if let Some(relation_name) = &self.relation_name {
+            write!(f, " on {}", relation_name)?;
+        }
+        if costs {
+            write!(
+                f,
+                "  (cost={:.2}..{:.2} rows={})",
+                self.startup_cost, self.total_cost, self.plan_rows
+            )?;
+            if let (Some(actual_rows), Some(actual_total_time)) =
+                (self.actual_rows, self.actual_total_time)
+            {
+                write!(f, " (actual time={:.3} rows={})", actual_total_time, actual_rows)?;
+            }
+        }
+        writeln!(f)?;
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1, costs)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `EXPLAIN` on the given SQL inside a transaction (or savepoint) which is
+/// always rolled back. Used by `#[derive(Query)]` and `#[derive(Statement)]`.
+pub fn explain(
+    connection: &mut impl postgres::GenericClient,
+    sql: &str,
+    parameters: &[&(dyn ToSql + Sync)],
+    options: ExplainOptions,
+) -> Result<Plan, Error> {
+    let sql = format!("EXPLAIN ({}) {}", options.to_sql(), sql);
+    // The transaction is rolled back when it is dropped
+    let mut transaction = connection.transaction()?;
+    let row = transaction.query_one(&sql, parameters)?;
+    match row.try_get::<_, PlanJson>(0) {
+        Ok(PlanJson(plan)) => Ok(plan),
+        Err(err) => Err(Error::new(ErrorKind::Explain, err)),
+    }
+}
+
+/// The output of `EXPLAIN (FORMAT JSON)`, decoded straight into a [Plan] so
+/// that a plan which can't be parsed is reported by `postgres` like any other
+/// value which can't be decoded.
+struct PlanJson(Plan);
+
+impl<'a> FromSql<'a> for PlanJson {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let json = Value::from_sql(ty, raw)?;
+        match Plan::from_json(&json) {
+            Some(plan) => Ok(PlanJson(plan)),
+            None => Err("the output of EXPLAIN (FORMAT JSON) is not a plan".into()),
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <Value as FromSql>::accepts(ty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(json: &str) -> Option<Plan> {
+        Plan::from_json(&serde_json::from_str(json).unwrap())
+    }
+
+    #[test]
+    fn parses_a_plan() {
+        // EXPLAIN (FORMAT JSON) SELECT * FROM plan_person WHERE id = 1
+        let plan = plan(include_str!("../testdata/index_scan.json")).unwrap();
+        assert_eq!(
+            plan.root,
+            PlanNode {
+                node_type: "Index Scan".to_owned(),
+                relation_name: Some("plan_person".to_owned()),
+                index_name: Some("plan_person_pkey".to_owned()),
+                startup_cost: 0.29,
+                total_cost: 8.30,
+                plan_rows: 1.0,
+                actual_rows: None,
+                actual_total_time: None,
+                shared_hit_blocks: None,
+                shared_read_blocks: None,
+                children: vec![],
+            }
+        );
+        assert_eq!(plan.planning_time, None);
+        assert_eq!(plan.execution_time, None);
+        assert_eq!(plan.index_names().collect::<Vec<_>>(), ["plan_person_pkey"]);
+    }
+
+    #[test]
+    fn parses_a_plan_with_analyze_and_buffers() {
+        // EXPLAIN (FORMAT JSON, ANALYZE, BUFFERS) SELECT p.name, t.name FROM
+        // plan_person p JOIN plan_team t ON t.id = p.team_id WHERE p.id < 50
+        let plan = plan(include_str!("../testdata/hash_join_analyze.json")).unwrap();
+        assert_eq!(plan.planning_time, Some(0.641));
+        assert_eq!(plan.execution_time, Some(0.175));
+        assert_eq!(
+            plan.nodes()
+                .map(|node| (node.node_type.as_str(), node.relation_name.as_deref()))
+                .collect::<Vec<_>>(),
+            [
+                ("Hash Join", None),
+                ("Index Scan", Some("plan_person")),
+                ("Hash", None),
+                ("Seq Scan", Some("plan_team")),
+            ]
+        );
+        let root = &plan.root;
+        assert_eq!((root.startup_cost, root.total_cost, root.plan_rows), (3.54, 12.52, 49.0));
+        assert_eq!(root.actual_rows, Some(49.0));
+        assert_eq!(root.actual_total_time, Some(0.076));
+        assert_eq!(root.shared_hit_blocks, Some(4));
+        assert_eq!(root.shared_read_blocks, Some(0));
+        assert_eq!(root.children[1].children[0].actual_rows, Some(100.0));
+    }
+
+    #[test]
+    fn shape_ignores_estimates() {
+        // EXPLAIN (FORMAT JSON) of the same join, before and after adding rows
+        // to both tables
+        let before = plan(include_str!("../testdata/hash_join.json")).unwrap();
+        let after = plan(include_str!("../testdata/hash_join_more_rows.json")).unwrap();
+        assert_ne!(before, after);
+        assert_eq!(before.shape(), after.shape());
+        assert_eq!(
+            before.shape(),
+            "Hash Join\n  ->  Index Scan using plan_person_pkey on plan_person\n  ->  Hash\n        ->  Seq Scan on plan_team\n"
+        );
+        // The timings and actual rows of EXPLAIN ANALYZE are dropped too
+        let analyzed = plan(include_str!("../testdata/hash_join_analyze.json")).unwrap();
+        assert_eq!(analyzed.shape(), before.shape());
+    }
+
+    #[test]
+    fn shape_changes_with_the_plan() {
+        // The same join for most of the people, which scans the table instead
+        // of the index
+        let index_scan = plan(include_str!("../testdata/hash_join.json")).unwrap();
+        let seq_scan = plan(include_str!("../testdata/large_join.json")).unwrap();
+        assert_eq!(
+            seq_scan.shape(),
+            "Hash Join\n  ->  Seq Scan on plan_person\n  ->  Hash\n        ->  Seq Scan on plan_team\n"
+        );
+        assert_ne!(index_scan.shape(), seq_scan.shape());
+    }
+
+    #[test]
+    fn rejects_output_which_is_not_a_plan() {
+        assert_eq!(plan("[]"), None);
+        assert_eq!(plan(r#"{"Plan": {}}"#), None);
+        assert_eq!(plan(r#"[{"Plan": {"Node Type": "Result"}}]"#), None);
+        assert_eq!(
+            plan(r#"[{"Plan": {"Node Type": "Result", "Startup Cost": 0, "Total Cost": 0.01, "Plan Rows": 1, "Plans": [{}]}}]"#),
+            None
+        );
+    }
+}
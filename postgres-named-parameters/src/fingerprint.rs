@@ -0,0 +1,296 @@
+/// Compute the fingerprint of the given SQL, i.e. the 64-bit FNV-1a hash of
+/// the SQL with comments removed, whitespace collapsed and any trailing `;`
+/// removed. This is the same as the `FINGERPRINT` of a statement or query
+/// derived with the same SQL (see [Query::FINGERPRINT](crate::Query::FINGERPRINT)).
+///
+/// Since `pg_stat_statements` records the SQL as it was sent (with `$1`, `$2`,
+/// etc. in place of the named parameters), this can be used to find which
+/// statement or query each row of `pg_stat_statements` belongs to. However,
+/// `pg_stat_statements` also replaces any constants in the SQL (e.g. `42` or
+/// `'active'`) with parameters, which this doesn't do, so the fingerprints of
+/// SQL containing constants won't match; pass such values as parameters
+/// instead. The SQL run by [query_exists](crate::Query::query_exists),
+/// [query_count](crate::Query::query_count) and
+/// [query_page](crate::PaginatedQuery::query_page) wraps the query's SQL, so
+/// compare it with
+/// [EXISTS_FINGERPRINT](crate::Query::EXISTS_FINGERPRINT),
+/// [COUNT_FINGERPRINT](crate::Query::COUNT_FINGERPRINT) and
+/// [PAGE_FINGERPRINTS](crate::PaginatedQuery::PAGE_FINGERPRINTS).
+///
+/// # Example
+/// ```no_run
+/// # use postgres_named_parameters::{sql_fingerprint, Query};
+/// # use postgres_from_row::FromRow;
+/// # #[derive(FromRow)]
+/// # struct Person {
+/// #     first_name: String,
+/// # }
+/// #[derive(Query)]
+/// #[query(sql = "SELECT * FROM Person WHERE id = @id", row = Person)]
+/// struct GetPerson {
+///     id: i32,
+/// }
+///
+/// fn main() -> Result<(), postgres::Error> {
+///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///         .unwrap_or("host=localhost user=postgres".to_owned());
+///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
+///
+///     for row in db.query("SELECT query, total_exec_time FROM pg_stat_statements", &[])? {
+///         if sql_fingerprint(row.get("query")) == GetPerson::FINGERPRINT {
+///             let total_exec_time: f64 = row.get("total_exec_time");
+///             println!("GetPerson has taken {} ms in total", total_exec_time);
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+///
+/// This is a `const fn`, which `#[derive(Query)]` and `#[derive(Statement)]`
+/// call to compute `FINGERPRINT`, so the two can't disagree.
+pub const fn sql_fingerprint(sql: &str) -> u64 {
+    let sql = sql.as_bytes();
+    let mut hasher = Hasher::new();
+    let mut pending_space = false;
+    let mut i = 0;
+
+    while i < sql.len() {
+        let byte = sql[i];
+        if byte == b'-' && byte_is(sql, i + 1, b'-') {
+            while i < sql.len() && sql[i] != b'\n' {
+                i += 1;
+            }
+            pending_space = true;
+            continue;
+        }
+        if byte == b'/' && byte_is(sql, i + 1, b'*') {
+            // Block comments can be nested
+            i += 2;
+            let mut depth = 1;
+            while depth > 0 && i < sql.len() {
+                if sql[i] == b'/' && byte_is(sql, i + 1, b'*') {
+                    depth += 1;
+                    i += 2;
+                } else if sql[i] == b'*' && byte_is(sql, i + 1, b'/') {
+                    depth -= 1;
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+            pending_space = true;
+            continue;
+        }
+        if matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | b'\x0b' | b'\x0c') {
+            pending_space = true;
+            i += 1;
+            continue;
+        }
+
+        if pending_space && !hasher.is_empty() {
+            hasher = hasher.push(b' ');
+        }
+        pending_space = false;
+        hasher = hasher.push(byte);
+        i += 1;
+        if byte == b'\'' || byte == b'"' {
+            // Copy quoted strings and identifiers as they are. A doubled quote
+            // is an escaped quote, which is copied as two quotes in a row. In
+            // escape strings (e.g. `E'it\'s'`), a backslash escapes the byte
+            // after it.
+            let escape_string = byte == b'\'' && is_escape_string_prefix(sql, i - 1);
+            while i < sql.len() {
+                hasher = hasher.push(sql[i]);
+                i += 1;
+                if escape_string && sql[i - 1] == b'\\' && i < sql.len() {
+                    hasher = hasher.push(sql[i]);
+                    i += 1;
+                } else if sql[i - 1] == byte {
+                    break;
+                }
+            }
+        } else if byte == b'$' && i < sql.len() && is_tag_start(sql[i]) {
+            // Copy dollar-quoted strings (e.g. `$$...$$` or `$body$...$body$`)
+            // as they are, but not parameters (e.g. `$1`)
+            let tag_start = i - 1;
+            while i < sql.len() {
+                hasher = hasher.push(sql[i]);
+                i += 1;
+                if sql[i - 1] == b'$' {
+                    break;
+                }
+            }
+            let tag_length = i - tag_start;
+            let body_start = i;
+            while i < sql.len() {
+                hasher = hasher.push(sql[i]);
+                i += 1;
+                if i - body_start >= tag_length && ends_with_tag(sql, i, tag_start, tag_length) {
+                    break;
+                }
+            }
+        }
+    }
+
+    hasher.fingerprint
+}
+
+/// A 64-bit FNV-1a hash of the normalized SQL, which also remembers the hash
+/// before any trailing `;` and spaces, since they are removed.
+struct Hasher {
+    hash: u64,
+    fingerprint: u64,
+    length: usize,
+}
+
+impl Hasher {
+    const fn new() -> Hasher {
+        Hasher {
+            hash: 0xcbf29ce484222325,
+            fingerprint: 0xcbf29ce484222325,
+            length: 0,
+        }
+    }
+
+    const fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    const fn push(self, byte: u8) -> Hasher {
+        let hash = (self.hash ^ byte as u64).wrapping_mul(0x100000001b3);
+        Hasher {
+            hash,
+            fingerprint: if byte == b';' || byte == b' ' { self.fingerprint } else { hash },
+            length: self.length + 1,
+        }
+    }
+}
+
+const fn byte_is(sql: &[u8], index: usize, byte: u8) -> bool {
+    index < sql.len() && sql[index] == byte
+}
+
+/// Whether the quote at `sql[quote]` starts an escape string, i.e. it follows
+/// an `E` which isn't the end of an identifier or keyword.
+const fn is_escape_string_prefix(sql: &[u8], quote: usize) -> bool {
+    quote >= 1
+        && (sql[quote - 1] == b'E' || sql[quote - 1] == b'e')
+        && (quote == 1 || !is_identifier_byte(sql[quote - 2]))
+}
+
+/// Whether the byte can be part of an identifier or keyword.
+const fn is_identifier_byte(byte: u8) -> bool {
+    byte == b'$' || byte == b'_' || byte.is_ascii_alphanumeric() || byte >= 0x80
+}
+
+/// Whether the byte after a `$` starts a dollar quote's tag (rather than a
+/// parameter number). Bytes of non-ASCII characters are allowed, as they are in
+/// identifiers.
+const fn is_tag_start(byte: u8) -> bool {
+    byte == b'$' || byte == b'_' || byte.is_ascii_alphabetic() || byte >= 0x80
+}
+
+/// Whether `sql[..end]` ends with the dollar quote's tag, `sql[tag_start..]`.
+const fn ends_with_tag(sql: &[u8], end: usize, tag_start: usize, tag_length: usize) -> bool {
+    let mut i = 0;
+    while i < tag_length {
+        if sql[end - tag_length + i] != sql[tag_start + i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sql_fingerprint;
+
+    /// The 64-bit FNV-1a hash of SQL which is already normalized.
+    fn fnv(normalized: &str) -> u64 {
+        normalized.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    #[test]
+    fn hashes_normalized_sql() {
+        assert_eq!(sql_fingerprint(""), fnv(""));
+        assert_eq!(sql_fingerprint("SELECT 1"), fnv("SELECT 1"));
+        // FNV-1a test vector
+        assert_eq!(sql_fingerprint("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn collapses_whitespace() {
+        assert_eq!(
+            sql_fingerprint("  SELECT\n\t*\r\n  FROM   Person  "),
+            fnv("SELECT * FROM Person")
+        );
+    }
+
+    #[test]
+    fn removes_comments() {
+        assert_eq!(
+            sql_fingerprint("SELECT 1 -- one\n+ 2 /* two */ + 3"),
+            fnv("SELECT 1 + 2 + 3")
+        );
+        assert_eq!(sql_fingerprint("SELECT/* a /* nested */ comment */1"), fnv("SELECT 1"));
+        assert_eq!(sql_fingerprint("-- only a comment"), fnv(""));
+        assert_eq!(sql_fingerprint("SELECT 1 /* unterminated"), fnv("SELECT 1"));
+    }
+
+    #[test]
+    fn removes_trailing_semicolons() {
+        assert_eq!(sql_fingerprint("SELECT 1;"), fnv("SELECT 1"));
+        assert_eq!(sql_fingerprint("SELECT 1 ; ;\n"), fnv("SELECT 1"));
+        assert_eq!(sql_fingerprint("SELECT ';'"), fnv("SELECT ';'"));
+    }
+
+    #[test]
+    fn keeps_quoted_text() {
+        assert_eq!(
+            sql_fingerprint("SELECT 'a  --b', \"c  /*d*/\" FROM t"),
+            fnv("SELECT 'a  --b', \"c  /*d*/\" FROM t")
+        );
+        assert_eq!(sql_fingerprint("SELECT 'it''s  here'"), fnv("SELECT 'it''s  here'"));
+    }
+
+    #[test]
+    fn keeps_escape_strings() {
+        assert_eq!(
+            sql_fingerprint(r"SELECT E'it\'s  -- here',  1"),
+            fnv(r"SELECT E'it\'s  -- here', 1")
+        );
+        assert_eq!(sql_fingerprint(r"SELECT e'a\\'  ,  2"), fnv(r"SELECT e'a\\' , 2"));
+        // A backslash only escapes in escape strings
+        assert_eq!(sql_fingerprint(r"SELECT 'a\'  ,  2"), fnv(r"SELECT 'a\' , 2"));
+        assert_eq!(
+            sql_fingerprint(r"SELECT name'a\'  ,  2"),
+            fnv(r"SELECT name'a\' , 2")
+        );
+    }
+
+    #[test]
+    fn keeps_dollar_quoted_text() {
+        assert_eq!(
+            sql_fingerprint("SELECT $$a  -- b$$,  $body$ $$  $body$"),
+            fnv("SELECT $$a  -- b$$, $body$ $$  $body$")
+        );
+        assert_eq!(sql_fingerprint("SELECT $é$a  b$é$"), fnv("SELECT $é$a  b$é$"));
+    }
+
+    #[test]
+    fn leaves_parameters_alone() {
+        assert_eq!(
+            sql_fingerprint("SELECT *\nFROM Person WHERE id = $1   AND age > $2;"),
+            fnv("SELECT * FROM Person WHERE id = $1 AND age > $2")
+        );
+    }
+
+    #[test]
+    fn can_be_computed_at_compile_time() {
+        const FINGERPRINT: u64 = sql_fingerprint("SELECT 1;");
+        assert_eq!(FINGERPRINT, sql_fingerprint("SELECT 1"));
+    }
+}
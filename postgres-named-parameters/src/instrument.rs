@@ -0,0 +1,83 @@
+/// Wrap the body of a generated method so that the current
+/// [QueryObserver](crate::QueryObserver) is told about it, inside a `tracing`
+/// span named after the statement or query if the `tracing` feature is
+/// enabled. Used by `#[derive(Query)]` and `#[derive(Statement)]`.
+///
+/// This is a macro rather than a function because span names have to be
+/// known at compile time, and because the derive macros can't tell whether the
+/// `tracing` feature is enabled.
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! instrument_for_derive_macro {
+    ($name:literal, $operation:literal, $function:literal, $sql:literal, $rows_field:literal, $rows:expr, $body:expr) => {{
+        use $crate::internal::tracing_for_derive_macro::tracing;
+        let span = tracing::info_span!(
+            $name,
+            db.system = "postgresql",
+            db.statement = $sql,
+            db.operation = $operation,
+            code.function = $function,
+            db.response.returned_rows = tracing::field::Empty,
+            db.response.affected_rows = tracing::field::Empty,
+            db.response.status_code = tracing::field::Empty,
+            otel.status_code = tracing::field::Empty,
+        );
+        $crate::internal::tracing_for_derive_macro::in_span(&span, $rows_field, $rows, || {
+            $crate::internal::observer_for_derive_macro::observe(
+                $crate::QueryInfo {
+                    name: $name,
+                    method: $function,
+                    sql: $sql,
+                },
+                $rows,
+                || $body,
+            )
+        })
+    }};
+}
+
+/// Wrap the body of a generated method so that the current
+/// [QueryObserver](crate::QueryObserver) is told about it. Used by
+/// `#[derive(Query)]` and `#[derive(Statement)]`.
+#[cfg(not(feature = "tracing"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! instrument_for_derive_macro {
+    ($name:literal, $operation:literal, $function:literal, $sql:literal, $rows_field:literal, $rows:expr, $body:expr) => {
+        $crate::internal::observer_for_derive_macro::observe(
+            $crate::QueryInfo {
+                name: $name,
+                method: $function,
+                sql: $sql,
+            },
+            $rows,
+            || $body,
+        )
+    };
+}
+
+/// Run `f` inside `span`, recording the number of rows returned or affected
+/// (as given by `rows`) in the field named `rows_field`, or the SQLSTATE if it
+/// fails.
+#[cfg(feature = "tracing")]
+pub fn in_span<T>(
+    span: &tracing::Span,
+    rows_field: &str,
+    rows: impl FnOnce(&T) -> u64,
+    f: impl FnOnce() -> Result<T, crate::Error>,
+) -> Result<T, crate::Error> {
+    let result = span.in_scope(f);
+    match &result {
+        Ok(value) => {
+            span.record(rows_field, rows(value));
+        }
+        Err(err) => {
+            span.record("otel.status_code", "ERROR");
+            if let Some(code) = err.code() {
+                span.record("db.response.status_code", code.code());
+            }
+        }
+    }
+    result
+}
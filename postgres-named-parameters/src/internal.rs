@@ -1,12 +1,230 @@
 #![doc(hidden)]
-// Export a wrapper around postgres_from_row::FromRow so we can reference it in
-// our #[derive(Query)] procedural macro. We do not re-export the
-// postgres_from_row crate because its #[derive(FromRow)] procedural macro does
-// not work properly unless the user directly adds postgres_from_row to their
-// Cargo.toml dependencies.
+// Export wrappers around DecodeRow so we can reference them in our
+// #[derive(Query)] and #[derive(Statement)] procedural macros. The wrappers
+// take the Kind marker as a separate type parameter so that the generated code
+// can leave it to be inferred (i.e. `try_from_row::<Self::Row, _>`).
 pub mod wrapper_for_derive_macro {
-    use postgres_from_row::FromRow;
-    pub fn try_from_row<T: FromRow>(row: &postgres::Row) -> Result<T, postgres::Error> {
-        T::try_from_row(row)
+    use crate::row::DecodeRow;
+    use crate::Error;
+    use postgres::fallible_iterator::FallibleIterator;
+    use postgres::types::ToSql;
+
+    pub fn try_from_row<T: DecodeRow<Kind>, Kind>(row: &postgres::Row) -> Result<T, Error> {
+        T::decode_row(row)
     }
+
+    pub fn try_from_row_iter<T: DecodeRow<Kind>, Kind>(
+        mut row_iter: postgres::RowIter<'_>,
+    ) -> Result<crate::Returned<T>, Error> {
+        let mut rows = vec![];
+        while let Some(row) = row_iter.next()? {
+            rows.push(try_from_row(&row)?);
+        }
+        // The number of rows affected is only known once the iterator has
+        // been exhausted
+        let rows_affected = row_iter.rows_affected().unwrap_or(rows.len() as u64);
+        Ok(crate::Returned {
+            rows_affected,
+            rows,
+        })
+    }
+
+    /// Like [postgres::GenericClient::query_one], but tells apart finding no
+    /// rows and finding too many rows.
+    pub fn query_one<T: DecodeRow<Kind>, Kind>(
+        connection: &mut impl postgres::GenericClient,
+        sql: &str,
+        parameters: &[&(dyn ToSql + Sync)],
+    ) -> Result<T, Error> {
+        match query_opt(connection, sql, parameters)? {
+            Some(row) => Ok(row),
+            None => Err(Error::row_count(crate::ErrorKind::NotFound, connection)),
+        }
+    }
+
+    /// Like [query_one], but runs the statement inside a transaction (or a
+    /// savepoint, if the connection is already a transaction) which is rolled
+    /// back unless exactly one row is returned and decoded.
+    pub fn execute_returning_one<T: DecodeRow<Kind>, Kind>(
+        connection: &mut impl postgres::GenericClient,
+        sql: &str,
+        parameters: &[&(dyn ToSql + Sync)],
+    ) -> Result<T, Error> {
+        let mut transaction = connection.transaction()?;
+        // The transaction is rolled back when it is dropped
+        let row = query_one(&mut transaction, sql, parameters)?;
+        transaction.commit()?;
+        Ok(row)
+    }
+
+    /// Like [postgres::GenericClient::query_opt], but decodes the row.
+    pub fn query_opt<T: DecodeRow<Kind>, Kind>(
+        connection: &mut impl postgres::GenericClient,
+        sql: &str,
+        parameters: &[&(dyn ToSql + Sync)],
+    ) -> Result<Option<T>, Error> {
+        let mut rows = connection.query_raw(sql, parameters.iter().copied())?;
+        let Some(row) = rows.next()? else {
+            return Ok(None);
+        };
+        if rows.next()?.is_some() {
+            drop(rows);
+            return Err(Error::row_count(crate::ErrorKind::TooManyRows, connection));
+        }
+        Ok(Some(try_from_row(&row)?))
+    }
+}
+
+// Export the wrapper which runs the body of each generated method, attaching
+// the name of the statement or query and its SQL to any error.
+pub mod error_for_derive_macro {
+    use crate::Error;
+
+    pub fn run<T>(
+        query: &'static str,
+        sql: &'static str,
+        f: impl FnOnce() -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        f().map_err(|err| err.with_context(query, sql))
+    }
+
+    /// Like [run], but for statements and queries with redacted parameters.
+    pub fn run_redacted<T>(
+        query: &'static str,
+        sql: &'static str,
+        f: impl FnOnce() -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        f().map_err(|err| err.with_context(query, sql).redact())
+    }
+}
+
+// Export the helper used by #[derive(Query)] and #[derive(Statement)] when the
+// `tag` parameter is provided (or the `sql-tags` feature is enabled).
+pub mod sql_tag_for_derive_macro {
+    pub use crate::sql_tag::with_runtime_tags;
+}
+
+// Export the helper used by the instrument_for_derive_macro! macro to tell the
+// current QueryObserver about each statement and query.
+pub mod observer_for_derive_macro {
+    pub use crate::observer::observe;
+}
+
+// Export the helpers used by the instrument_for_derive_macro! macro when the
+// `tracing` feature is enabled.
+#[cfg(feature = "tracing")]
+pub mod tracing_for_derive_macro {
+    pub use crate::instrument::in_span;
+    pub use tracing;
+}
+
+// Export the pagination helpers used by #[derive(Query)] when the `paginate`
+// parameter is provided.
+pub mod pagination_for_derive_macro {
+    pub use crate::page::{query_keyset_page, query_offset_page};
+}
+
+// Export the helper used by #[derive(Query)] and #[derive(Statement)] when the
+// `timeout` parameter is provided.
+pub mod timeout_for_derive_macro {
+    pub use crate::timeout::with_statement_timeout;
+}
+
+// Export the helper used by #[derive(Query)] and #[derive(Statement)] to
+// implement DebugSql.
+pub mod debug_sql_for_derive_macro {
+    pub use crate::debug_sql::to_debug_sql;
+}
+
+// Export the helper used by #[derive(Query)] and #[derive(Statement)] to
+// implement Explain.
+pub mod explain_for_derive_macro {
+    pub use crate::explain::explain;
+}
+
+// Export the helper used by #[derive(Query)] when the `read_only` parameter is
+// provided.
+pub mod read_only_for_derive_macro {
+    pub use crate::read_only::with_read_only;
+}
+
+// Export the helpers used by #[derive(Statement)] when the `on_constraint`
+// parameter is provided.
+pub mod constraint_for_derive_macro {
+    use crate::{Error, StatementError};
+    use postgres::error::SqlState;
+
+    /// Return the name of the constraint violated by the statement, or the
+    /// name of the column for not-null violations (which have no constraint).
+    pub fn violated_constraint(error: &Error) -> Option<&str> {
+        let db_error = error.as_postgres_error().as_db_error()?;
+        match db_error.code() {
+            &SqlState::UNIQUE_VIOLATION
+            | &SqlState::FOREIGN_KEY_VIOLATION
+            | &SqlState::CHECK_VIOLATION
+            | &SqlState::EXCLUSION_VIOLATION => db_error.constraint(),
+            &SqlState::NOT_NULL_VIOLATION => db_error.column(),
+            _ => None,
+        }
+    }
+
+    pub fn statement_error<E>(error: Error, violation: Option<E>) -> StatementError<E> {
+        match violation {
+            Some(violation) => StatementError::Constraint { violation, error },
+            None => StatementError::Other(error),
+        }
+    }
+}
+
+// Export the helper used by #[derive(Statement)] when the `expect_rows`
+// parameter is provided.
+pub mod expect_rows_for_derive_macro {
+    use crate::{Error, ErrorKind, ExpectedRows};
+
+    /// Run `f` inside a transaction (or a savepoint, if the connection is
+    /// already a transaction), and roll it back if the number of rows affected
+    /// (as given by `rows_affected`) is not what was expected.
+    pub fn with_expected_rows<T>(
+        connection: &mut impl postgres::GenericClient,
+        expected: ExpectedRows,
+        rows_affected: impl FnOnce(&T) -> u64,
+        f: impl FnOnce(&mut postgres::Transaction<'_>) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let mut transaction = connection.transaction()?;
+        let result = f(&mut transaction)?;
+        let actual = rows_affected(&result);
+        if !expected.contains(actual) {
+            // The transaction is rolled back when it is dropped
+            return Err(Error::row_count(
+                ErrorKind::RowCountMismatch { expected, actual },
+                &mut transaction,
+            ));
+        }
+        transaction.commit()?;
+        Ok(result)
+    }
+}
+
+// Export the helper used by #[derive(RowColumns)].
+pub mod row_columns_for_derive_macro {
+    use postgres::types::FromSqlOwned;
+    use postgres::Column;
+
+    /// Check that a column with the given name is returned and can be decoded
+    /// into `T`.
+    pub fn check_named_column<T: FromSqlOwned>(columns: &[Column], name: &str) -> Option<String> {
+        match columns.iter().find(|column| column.name() == name) {
+            Some(column) => crate::row::check_column::<T>(column),
+            None => Some(format!("returns no column named `{}`", name)),
+        }
+    }
+}
+
+// Export the helpers used by #[derive(Query)] and #[derive(Statement)] to
+// implement Validate, and to register themselves for validate_all.
+pub mod registry_for_derive_macro {
+    pub use crate::registry::{check_parameters, CheckDecodeRow, CheckRowColumns, ColumnChecker, Validate};
+
+    #[cfg(feature = "registry")]
+    pub use inventory;
 }
@@ -1,12 +1,57 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../README.md")]
+pub mod connection;
+mod debug_sql;
+mod error;
+mod explain;
+mod fingerprint;
+mod instrument;
 pub mod internal;
+mod meta;
+mod notice;
+mod observer;
+mod page;
+mod read_only;
+mod registry;
+pub mod row;
+mod snapshot;
+mod sql_tag;
+mod timeout;
+mod transaction;
 
+pub use connection::Connection;
+pub use debug_sql::DebugSql;
+pub use error::{Error, ErrorKind, ExpectedRows, StatementError};
+pub use explain::{Explain, ExplainOptions, Plan, PlanNode};
+pub use fingerprint::sql_fingerprint;
+pub use meta::QueryMeta;
+pub use notice::{collect_notices, notice_callback};
+pub use observer::{
+    clear_query_observer, set_query_observer, with_query_observer, QueryInfo, QueryObserver,
+};
 pub use postgres;
+/// See the [BulkInsert] docs for details.
+pub use postgres_named_parameters_derive::BulkInsert;
 /// See the [Query] docs for details.
 pub use postgres_named_parameters_derive::Query;
+/// See the [RowColumns] docs for details.
+pub use postgres_named_parameters_derive::RowColumns;
 /// See the [Statement] docs for details.
 pub use postgres_named_parameters_derive::Statement;
+/// See the [Transaction] docs for details.
+pub use postgres_named_parameters_derive::Transaction;
+pub use page::{InvalidCursorToken, KeysetCursor, OffsetCursor, Page, PaginatedQuery};
+#[cfg(feature = "registry")]
+pub use registry::{
+    registered_queries, sql_catalog, validate_all, Mismatch, RegisteredQuery, ValidationError,
+};
+pub use row::{DecodeRow, RowColumns};
+pub use snapshot::assert_plan_snapshot;
+pub use sql_tag::{clear_sql_tag_hook, set_sql_tag_hook, SqlTagHook};
+pub use timeout::is_timeout;
+pub use transaction::{
+    run_in_transaction, with_savepoint, RetryableError, SavepointError, TransactionOptions,
+};
 
 /// A `Statement` is a SQL statement that, unlike a [Query], does not return rows.
 /// Instead, it returns the number of rows that have been affected by the
@@ -15,6 +60,7 @@ pub use postgres_named_parameters_derive::Statement;
 /// # Example
 /// `Statement` can be derived like so:
 /// ```no_run
+/// # use postgres_named_parameters::Statement;
 /// #[derive(Statement)]
 /// #[statement(sql = "DELETE FROM Person WHERE id = @id")]
 /// struct DeletePerson {
@@ -25,12 +71,13 @@ pub use postgres_named_parameters_derive::Statement;
 ///
 /// It then can be used like so:
 /// ```no_run
+/// # use postgres_named_parameters::Statement;
 /// # #[derive(Statement)]
 /// # #[statement(sql = "DELETE FROM Person WHERE id = @id")]
 /// # struct DeletePerson {
 /// #     id: i32
 /// # }
-/// fn main() -> Result<(), postgres::Error> {
+/// fn main() -> Result<(), postgres_named_parameters::Error> {
 ///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
 ///         .unwrap_or("host=localhost user=postgres".to_owned());
 ///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
@@ -53,22 +100,215 @@ pub use postgres_named_parameters_derive::Statement;
 ///     * Unlike [Query], there is no `row` parameter because
 ///       `Statement` does not return rows (but rather a count of the number of
 ///       rows affected)
+///     * For statements with a `RETURNING` clause, you can provide the optional
+///       `returning` parameter to also implement [ReturningStatement] (see its
+///       docs for details)
+///     * The optional `timeout` parameter (e.g. `timeout = "2s"`) limits how
+///       long the statement may run. The units `ms`, `s`, `min` and `h` are
+///       supported. The statement is run inside a transaction (or a savepoint, if
+///       you pass a transaction) using `SET LOCAL statement_timeout`. Use
+///       [ErrorKind::Timeout] to check whether an error was caused by the
+///       timeout.
+///     * The optional `requires_transaction` flag only allows the statement
+///       to be run on a [postgres::Transaction], so that passing a
+///       [postgres::Client] is a compile-time error (see [Connection])
+///     * The optional `on_constraint(...)` parameter classifies unique,
+///       foreign key, check, exclusion and not-null violations into your own
+///       error enum, e.g. `on_constraint("person_email_key" =>
+///       PersonError::EmailTaken)`. The statement then returns a
+///       [StatementError] (see its docs for details)
+///     * The optional `expect_rows` parameter checks how many rows the
+///       statement affected, e.g. `expect_rows = 1`, `expect_rows = "0..=1"` or
+///       `expect_rows = "at_least(1)"`. The statement is run inside a
+///       transaction (or a savepoint, if you pass a transaction), which is
+///       rolled back if the count doesn't match, and an error with
+///       [ErrorKind::RowCountMismatch] is returned
+///     * The optional `tag` flag appends a comment saying where the statement
+///       came from (e.g. `/* query='DeletePerson',file='src/db.rs',line=42 */`) to
+///       its SQL, so that it can be recognised in `pg_stat_activity` and the
+///       Postgres logs. The `sql-tags` feature tags every statement and
+///       query. See [set_sql_tag_hook] to add tags such as request IDs.
+///     * The optional `redact` flag redacts every parameter (see
+///       [Redacting parameters](#redacting-parameters))
+/// * Fields holding sensitive values (e.g. passwords or tokens) can be marked
+///   with `#[param(redact)]` (see [Redacting parameters](#redacting-parameters))
 /// * At compile time, the derive macro will check that the parameter names you
 ///   used in your query match the field names defined in the struct. A mismatch
 ///   (e.g. using "@idd" instead of "@id" when the field name is `id`) will
 ///   cause a compiler error.
 /// * If you want to include a single literal `@` in your SQL, you must escape
 ///   it by doubling it (`@@`)
+///
+/// # Redacting parameters
+/// Fields holding sensitive values (e.g. passwords or tokens) can be marked
+/// with `#[param(redact)]`, or every field can be redacted using the `redact`
+/// flag of `#[statement(...)]` or `#[query(...)]` (in which case
+/// `#[param(redact = false)]` opts a field out). The values of redacted fields
+/// are rendered as `<redacted>` by [DebugSql], and the `DETAIL` of database
+/// errors (which often contains the offending values) is hidden when the error
+/// is displayed or passed up using `?` (see [Error]). Tracing spans and
+/// [QueryObserver]s are only given the numbered SQL, never the values of
+/// parameters. [BulkInsert] supports the same using `#[bulk_insert(redact)]`.
 pub trait Statement {
+    /// What the statement requires of the connection it is run on: either
+    /// [AnyConnection](connection::AnyConnection) or
+    /// [InTransaction](connection::InTransaction). You choose this in the
+    /// derive macro using the `requires_transaction` parameter.
+    type Requires;
+
+    /// The error returned by the statement. This is [Error], unless the
+    /// `on_constraint` parameter was provided to the derive macro, in which
+    /// case it is a [StatementError].
+    type Error: From<Error> + Into<Error>;
+
+    /// A stable hash of the statement's SQL, ignoring comments and whitespace. Use
+    /// [sql_fingerprint] to compute the fingerprint of SQL from elsewhere
+    /// (e.g. `pg_stat_statements`) and compare it with this.
+    const FINGERPRINT: u64;
+
     /// Execute a given statement on a given database connection or transaction,
     /// and return the number of rows that were affected.
     ///
     /// For the sole argument you can pass either a database connection (i.e.
-    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]).
+    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]),
+    /// unless the `requires_transaction` parameter was provided (see
+    /// [Connection]).
     fn execute_statement(
         &self,
-        connection: &mut impl postgres::GenericClient,
-    ) -> Result<u64, postgres::error::Error>;
+        connection: &mut impl Connection<Self::Requires>,
+    ) -> Result<u64, Self::Error>;
+
+    /// Like [execute_statement](Statement::execute_statement), but also return
+    /// the notices (e.g. `NOTICE` and `WARNING` messages) the database sent
+    /// while the statement ran. The connection must have been created with
+    /// [notice_callback] (see [collect_notices]).
+    fn execute_statement_with_notices(
+        &self,
+        connection: &mut impl Connection<Self::Requires>,
+    ) -> Result<(u64, Vec<postgres::error::DbError>), Self::Error> {
+        let (result, notices) = collect_notices(|| self.execute_statement(connection));
+        result.map(|rows_affected| (rows_affected, notices))
+    }
+}
+
+/// A `ReturningStatement` is a [Statement] with a `RETURNING` clause, whose
+/// returned rows are decoded in addition to counting the rows affected.
+///
+/// # Example
+/// `ReturningStatement` is implemented by `#[derive(Statement)]` when you
+/// provide the `returning` parameter:
+/// ```no_run
+/// # use postgres_from_row::FromRow;
+/// # use postgres_named_parameters::Statement;
+/// #[derive(FromRow, Debug)]
+/// struct InsertedPerson {
+///     id: i32,
+///     created_at: std::time::SystemTime,
+/// }
+///
+/// #[derive(Statement)]
+/// #[statement(
+///     sql = "
+///       INSERT INTO Person (first_name, last_name)
+///       VALUES (@first_name, @last_name)
+///       RETURNING id, created_at",
+///     // Specify what type each returned row should decode to
+///     returning = InsertedPerson
+/// )]
+/// struct InsertPerson<'a> {
+///     first_name: &'a str,
+///     last_name: &'a str,
+/// }
+/// ```
+///
+/// It then can be used like so:
+/// ```no_run
+/// # use postgres_from_row::FromRow;
+/// # use postgres_named_parameters::{ReturningStatement, Statement};
+/// # #[derive(FromRow, Debug)]
+/// # struct InsertedPerson {
+/// #     id: i32,
+/// #     created_at: std::time::SystemTime,
+/// # }
+/// # #[derive(Statement)]
+/// # #[statement(
+/// #     sql = "
+/// #       INSERT INTO Person (first_name, last_name)
+/// #       VALUES (@first_name, @last_name)
+/// #       RETURNING id, created_at",
+/// #     returning = InsertedPerson
+/// # )]
+/// # struct InsertPerson<'a> {
+/// #     first_name: &'a str,
+/// #     last_name: &'a str,
+/// # }
+/// fn main() -> Result<(), postgres_named_parameters::Error> {
+///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///         .unwrap_or("host=localhost user=postgres".to_owned());
+///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
+///
+///     let inserted = InsertPerson {
+///         first_name: "John",
+///         last_name: "Doe",
+///     }
+///     .execute_returning_one(&mut db)?;
+///
+///     println!("Inserted person with id {}", inserted.id);
+///     Ok(())
+/// }
+/// ```
+///
+/// # Notes
+/// * The `returning` parameter must implement [DecodeRow] (see the [Query]
+///   docs for details).
+/// * Because `ReturningStatement` is a [Statement], you can still use
+///   [execute_statement](Statement::execute_statement) if you only need the
+///   count of the number of rows affected.
+pub trait ReturningStatement: Statement {
+    /// The type that each individual row returned by the statement should
+    /// decode to. You specify this type in the derive macro using the
+    /// `returning` parameter. Note that this type must implement [DecodeRow].
+    type Row;
+
+    /// Execute the statement and return all the rows it returned, along with
+    /// the number of rows that were affected.
+    ///
+    /// For the sole argument you can pass either a database connection (i.e.
+    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]),
+    /// unless the `requires_transaction` parameter was provided (see
+    /// [Connection]).
+    fn execute_returning_all(
+        &self,
+        connection: &mut impl Connection<Self::Requires>,
+    ) -> Result<Returned<Self::Row>, Self::Error>;
+
+    /// Execute the statement, expecting it to return exactly one row. Return an
+    /// [ErrorKind::NotFound] error if no rows are returned, and an
+    /// [ErrorKind::TooManyRows] error if more than one row is returned.
+    ///
+    /// The statement is run inside a transaction (or a savepoint, if you pass a
+    /// transaction), which is rolled back unless exactly one row is returned
+    /// and decoded. This means that e.g. an `UPDATE ... RETURNING` which
+    /// matches more rows than expected has no effect.
+    ///
+    /// For the sole argument you can pass either a database connection (i.e.
+    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]),
+    /// unless the `requires_transaction` parameter was provided (see
+    /// [Connection]).
+    fn execute_returning_one(
+        &self,
+        connection: &mut impl Connection<Self::Requires>,
+    ) -> Result<Self::Row, Self::Error>;
+}
+
+/// The rows returned by a [ReturningStatement], along with the number of rows
+/// the statement affected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Returned<T> {
+    /// The number of rows that were affected by the statement.
+    pub rows_affected: u64,
+    /// The rows returned by the statement's `RETURNING` clause.
+    pub rows: Vec<T>,
 }
 
 /// A `Query` is a SQL query that returns rows from the database.
@@ -76,6 +316,8 @@ pub trait Statement {
 /// # Example
 /// `Query` can be derived like so:
 /// ```no_run
+/// # use postgres_from_row::FromRow;
+/// # use postgres_named_parameters::Query;
 /// # #[derive(FromRow, Debug)]
 /// # struct Person {
 /// #     first_name: String,
@@ -100,13 +342,14 @@ pub trait Statement {
 ///     name: &'a str,
 /// }
 /// ```
-/// Note that the struct you specify for `row` must implement
-/// [FromRow](postgres_from_row::FromRow). You can do this by using
-/// `#[derive(FromRow)]`, which you can get by adding
+/// Note that the type you specify for `row` must implement [DecodeRow]. For a
+/// struct, this means implementing [FromRow](postgres_from_row::FromRow),
+/// which you can do by using `#[derive(FromRow)]`, which you can get by adding
 /// [postgres-from-row](https://crates.io/crates/postgres-from-row) to your
 /// `Cargo.toml`. (Note: as of time of writing, [postgres-from-row](https://crates.io/crates/postgres-from-row) does not yet
 /// support [borrowed fields](https://github.com/remkop22/postgres-from-row/issues/12).)
 /// ```no_run
+/// # use postgres_from_row::FromRow;
 /// #[derive(FromRow, Debug)]
 /// struct Person {
 ///     first_name: String,
@@ -115,9 +358,15 @@ pub trait Statement {
 ///     alive: bool,
 /// }
 /// ````
+/// Alternatively, if your query only returns a single column (e.g. `SELECT
+/// count(*)`) or a few columns, you can skip defining a struct and use a
+/// scalar type (e.g. `row = i64`) or a tuple (e.g. `row = (i32, String)`)
+/// instead. See [DecodeRow] for details.
 ///
 /// Your can then use your query like this:
 /// ```no_run
+/// # use postgres_from_row::FromRow;
+/// # use postgres_named_parameters::Query;
 /// # #[derive(FromRow, Debug)]
 /// # struct Person {
 /// #     first_name: String,
@@ -125,6 +374,7 @@ pub trait Statement {
 /// #     hobby: Option<String>,
 /// #     alive: bool,
 /// # }
+/// # #[derive(Query)]
 /// # #[query(
 /// #     // Write the query using named parameters
 /// #     sql = "
@@ -139,7 +389,7 @@ pub trait Statement {
 /// #     alive: bool,
 /// #     name: &'a str,
 /// # }
-/// fn main() -> Result<(), postgres::Error> {
+/// fn main() -> Result<(), postgres_named_parameters::Error> {
 ///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
 ///         .unwrap_or("host=localhost user=postgres".to_owned());
 ///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
@@ -157,6 +407,14 @@ pub trait Statement {
 /// At compile time, the SQL is transformed to use numbered parameters. For
 /// example, the above query will roughly desugar to:
 /// ```no_run
+/// # use postgres_from_row::FromRow;
+/// # #[derive(FromRow)]
+/// # struct Person {
+/// #     first_name: String,
+/// #     last_name: String,
+/// #     hobby: Option<String>,
+/// #     alive: bool,
+/// # }
 /// # fn main() -> Result<(), postgres::Error> {
 /// #     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
 /// #         .unwrap_or("host=localhost user=postgres".to_owned());
@@ -183,8 +441,33 @@ pub trait Statement {
 ///   attribute `#[statement(sql = "...", row = ...)`
 ///     * Both the `sql` and `row` parameters are required
 ///     * The `sql` parameter must be a string literal
-///     * The `row` parameter must implement
-///       [FromRow](postgres_from_row::FromRow) (see above).
+///     * The `row` parameter must implement [DecodeRow] (see above).
+///     * The optional `paginate` parameter also implements [PaginatedQuery],
+///       which fetches the rows one page at a time (see its docs for details)
+///     * The optional `timeout` parameter (e.g. `timeout = "2s"`) limits how
+///       long the query may run. The units `ms`, `s`, `min` and `h` are
+///       supported. The query is run inside a transaction (or a savepoint, if
+///       you pass a transaction) using `SET LOCAL statement_timeout`. Use
+///       [ErrorKind::Timeout] to check whether an error was caused by the
+///       timeout.
+///     * The optional `requires_transaction` flag only allows the query to be
+///       run on a [postgres::Transaction], so that passing a
+///       [postgres::Client] is a compile-time error (see [Connection])
+///     * The optional `read_only` flag guarantees that the query never writes
+///       to the database, and sets [READ_ONLY](Query::READ_ONLY) to `true`. The
+///       query is run inside a read-only transaction, or a read-only savepoint
+///       if you pass a [postgres::Transaction] (the rest of that transaction
+///       can still write).
+///     * The optional `tag` flag appends a comment saying where the query
+///       came from (e.g. `/* query='GetPeople',file='src/db.rs',line=42 */`) to
+///       its SQL, so that it can be recognised in `pg_stat_activity` and the
+///       Postgres logs. The `sql-tags` feature tags every statement and
+///       query. See [set_sql_tag_hook] to add tags such as request IDs.
+///     * The optional `redact` flag redacts every parameter (see
+///       [Redacting parameters](Statement#redacting-parameters))
+/// * Fields holding sensitive values (e.g. passwords or tokens) can be marked
+///   with `#[param(redact)]`, as for [Statement]s (see
+///   [Redacting parameters](Statement#redacting-parameters))
 /// * At compile time, the derive macro will check that the parameter names you
 ///   used in your query match the field names defined in the struct. A mismatch
 ///   (e.g. using "@naame" instead of "@name" when the field name is `name`) will
@@ -194,41 +477,333 @@ pub trait Statement {
 pub trait Query {
     /// The type that each individual row returned from the query should decode
     /// to. You specify this type in the derive macro using the `row` parameter.
-    /// Note that this type must implement
-    /// [FromRow](postgres_from_row::FromRow) on the struct you intend to decode
-    /// each row to. You can do this by using
-    /// `#[derive(FromRow)`, which you can get by adding
-    /// [postgres-from-row](https://crates.io/crates/postgres-from-row) to your
-    /// `Cargo.toml`.
-    type Row: postgres_from_row::FromRow;
+    /// Note that this type must implement [DecodeRow], e.g. by using
+    /// `#[derive(FromRow)]` on the struct you intend to decode each row to, or
+    /// by using a scalar or tuple type.
+    type Row;
+
+    /// What the query requires of the connection it is run on: either
+    /// [AnyConnection](connection::AnyConnection) or
+    /// [InTransaction](connection::InTransaction). You choose this in the
+    /// derive macro using the `requires_transaction` parameter.
+    type Requires;
+
+    /// Whether the query is guaranteed not to write to the database, in which
+    /// case it could be sent to a read-only replica. This is `true` when the
+    /// `read_only` parameter is provided to the derive macro.
+    const READ_ONLY: bool = false;
+
+    /// A stable hash of the query's SQL, ignoring comments and whitespace. Use
+    /// [sql_fingerprint] to compute the fingerprint of SQL from elsewhere
+    /// (e.g. `pg_stat_statements`) and compare it with this.
+    const FINGERPRINT: u64;
+
+    /// The fingerprint of the SQL run by [query_exists](Query::query_exists),
+    /// which wraps the query's SQL in `SELECT EXISTS (...)` and so is recorded
+    /// separately by `pg_stat_statements`.
+    const EXISTS_FINGERPRINT: u64;
+
+    /// The fingerprint of the SQL run by [query_count](Query::query_count),
+    /// which wraps the query's SQL in `SELECT count(*) FROM (...)` and so is
+    /// recorded separately by `pg_stat_statements`.
+    const COUNT_FINGERPRINT: u64;
 
     /// Run the query and return all the rows in a vector.
     ///
     /// For the sole argument you can pass either a database connection (i.e.
-    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]).
+    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]),
+    /// unless the `requires_transaction` parameter was provided (see
+    /// [Connection]).
     fn query_all(
         &self,
-        connection: &mut impl postgres::GenericClient,
-    ) -> Result<Vec<Self::Row>, postgres::error::Error>;
+        connection: &mut impl Connection<Self::Requires>,
+    ) -> Result<Vec<Self::Row>, Error>;
+
+    /// Like [query_all](Query::query_all), but also return the notices (e.g.
+    /// `NOTICE` and `WARNING` messages from `RAISE NOTICE` in PL/pgSQL
+    /// functions) the database sent while the query ran. The connection must
+    /// have been created with [notice_callback] (see [collect_notices]).
+    fn query_all_with_notices(
+        &self,
+        connection: &mut impl Connection<Self::Requires>,
+    ) -> Result<(Vec<Self::Row>, Vec<postgres::error::DbError>), Error> {
+        let (result, notices) = collect_notices(|| self.query_all(connection));
+        result.map(|rows| (rows, notices))
+    }
 
     /// Run the query, expecting exactly one or zero rows. Return `Ok(None)` if
-    /// there are no rows, and return an `Err` if there is more than one row.
-    /// See also: [query_one](Query::query_one).
+    /// there are no rows, and return an [ErrorKind::TooManyRows] error if there
+    /// is more than one row. See also: [query_one](Query::query_one).
     ///
     /// For the sole argument you can pass either a database connection (i.e.
-    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]).
+    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]),
+    /// unless the `requires_transaction` parameter was provided (see
+    /// [Connection]).
     fn query_opt(
         &self,
-        connection: &mut impl postgres::GenericClient,
-    ) -> Result<Option<Self::Row>, postgres::error::Error>;
+        connection: &mut impl Connection<Self::Requires>,
+    ) -> Result<Option<Self::Row>, Error>;
 
-    /// Run the query, expecting exactly one row. Return an `Err` if no rows or
-    /// more than one row are returned. See also: [query_opt](Query::query_opt).
+    /// Run the query, expecting exactly one row. Return an
+    /// [ErrorKind::NotFound] error if no rows are returned, and an
+    /// [ErrorKind::TooManyRows] error if more than one row is returned. See
+    /// also: [query_opt](Query::query_opt).
     ///
     /// For the sole argument you can pass either a database connection (i.e.
-    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]).
+    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]),
+    /// unless the `requires_transaction` parameter was provided (see
+    /// [Connection]).
     fn query_one(
+        &self,
+        connection: &mut impl Connection<Self::Requires>,
+    ) -> Result<Self::Row, Error>;
+
+    /// Check whether the query returns any rows, without decoding them. The
+    /// query is run as `SELECT EXISTS (...)`, with the same parameters.
+    ///
+    /// For the sole argument you can pass either a database connection (i.e.
+    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]),
+    /// unless the `requires_transaction` parameter was provided (see
+    /// [Connection]).
+    fn query_exists(
+        &self,
+        connection: &mut impl Connection<Self::Requires>,
+    ) -> Result<bool, Error>;
+
+    /// Count how many rows the query returns, without decoding them. The query
+    /// is run as `SELECT count(*) FROM (...) _`, with the same parameters.
+    ///
+    /// For the sole argument you can pass either a database connection (i.e.
+    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]),
+    /// unless the `requires_transaction` parameter was provided (see
+    /// [Connection]).
+    fn query_count(
+        &self,
+        connection: &mut impl Connection<Self::Requires>,
+    ) -> Result<i64, Error>;
+}
+
+/// A `BulkInsert` is a row type that can be inserted into a table many rows at
+/// a time using a single statement.
+///
+/// # Example
+/// `BulkInsert` can be derived like so:
+/// ```no_run
+/// # use postgres_named_parameters::BulkInsert;
+/// #[derive(BulkInsert)]
+/// #[bulk_insert(table = "Person")]
+/// struct Person {
+///     first_name: String,
+///     last_name: String,
+///     hobby: Option<String>,
+///     alive: bool,
+/// }
+/// ```
+///
+/// It then can be used like so:
+/// ```no_run
+/// # use postgres_named_parameters::BulkInsert;
+/// # #[derive(BulkInsert)]
+/// # #[bulk_insert(table = "Person")]
+/// # struct Person {
+/// #     first_name: String,
+/// #     last_name: String,
+/// #     hobby: Option<String>,
+/// #     alive: bool,
+/// # }
+/// fn main() -> Result<(), postgres_named_parameters::Error> {
+///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///         .unwrap_or("host=localhost user=postgres".to_owned());
+///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
+///
+///     let people = vec![
+///         Person {
+///             first_name: "John".into(),
+///             last_name: "Doe".into(),
+///             hobby: None,
+///             alive: true,
+///         },
+///     ];
+///     let insert_count = Person::insert_many(&mut db, &people)?;
+///
+///     println!("Inserted {} people", insert_count);
+///     Ok(())
+/// }
+/// ```
+/// At compile time, the derive macro generates a statement which splits the
+/// rows into one array per column and reassembles them in Postgres. For
+/// example, the above roughly desugars to:
+/// ```no_run
+/// # struct Person {
+/// #     first_name: String,
+/// #     last_name: String,
+/// #     hobby: Option<String>,
+/// #     alive: bool,
+/// # }
+/// # fn main() -> Result<(), postgres::Error> {
+/// #     let mut db = postgres::Client::connect("", postgres::NoTls)?;
+/// #     let people: Vec<Person> = vec![];
+/// let insert_count = db.execute(
+///     "INSERT INTO Person (first_name, last_name, hobby, alive)
+///      SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::BOOL[])",
+///     &[
+///         &people.iter().map(|p| &p.first_name).collect::<Vec<_>>(),
+///         &people.iter().map(|p| &p.last_name).collect::<Vec<_>>(),
+///         &people.iter().map(|p| &p.hobby).collect::<Vec<_>>(),
+///         &people.iter().map(|p| &p.alive).collect::<Vec<_>>(),
+///     ],
+/// )?;
+/// #   Ok(())
+/// # }
+/// ```
+///
+/// # Notes
+/// * In order to use `#[derive(BulkInsert)]`, you must also provide the helper
+///   attribute `#[bulk_insert(table = "...")]`
+///     * The `table` parameter is required and must be a string literal
+///     * The optional `on_conflict` parameter is appended to the statement
+///       after `ON CONFLICT`, e.g. `on_conflict = "(first_name) DO NOTHING"`
+/// * Each field is inserted into the column of the same name. The SQL type of
+///   each column is inferred from the field's Rust type (looking through
+///   `Option`s and references) for `bool`, `i8`, `i16`, `i32`, `i64`, `u32`,
+///   `f32`, `f64`, `String`, `&str`, `Vec<u8>`, `&[u8]`, `SystemTime` and
+///   `IpAddr`. Fields can be customized with the helper attribute
+///   `#[bulk_insert(...)]`:
+///     * `sql_type = "..."` overrides the inferred SQL type (and is required
+///       for any other type), e.g. `sql_type = "TIMESTAMPTZ"`
+///     * `column = "..."` overrides the column name
+/// * The `DETAIL` of database errors (e.g. the values of a row which violates
+///   a unique constraint) is hidden if any field is marked with
+///   `#[bulk_insert(redact)]`, or if the struct is, as for [Statement]s (see
+///   [Redacting parameters](Statement#redacting-parameters))
+/// * Array fields (e.g. `Vec<String>`) are a compile-time error, because
+///   `UNNEST` flattens an array of arrays instead of returning one array per
+///   row. Structs with no fields are also a compile-time error.
+pub trait BulkInsert: Sized {
+    /// Insert all the given rows using a single statement, and return the
+    /// number of rows that were inserted.
+    ///
+    /// For the first argument you can pass either a database connection (i.e.
+    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]).
+    fn insert_many(
+        connection: &mut impl postgres::GenericClient,
+        rows: &[Self],
+    ) -> Result<u64, Error>;
+}
+
+/// A `Transaction` is a group of [Statement]s and [Query]s that are run one
+/// after another inside a single transaction, so that either all of them take
+/// effect or none of them do.
+///
+/// # Example
+/// `Transaction` can be derived for a struct whose fields are statements or
+/// queries:
+/// ```no_run
+/// # use postgres_named_parameters::{Statement, Transaction};
+/// #[derive(Statement)]
+/// #[statement(
+///     sql = "INSERT INTO Person (first_name, last_name) VALUES (@first_name, @last_name) RETURNING id",
+///     returning = i32
+/// )]
+/// struct InsertPerson<'a> {
+///     first_name: &'a str,
+///     last_name: &'a str,
+/// }
+///
+/// #[derive(Statement)]
+/// #[statement(sql = "INSERT INTO Audit (message) VALUES (@message)")]
+/// struct InsertAudit<'a> {
+///     message: &'a str,
+/// }
+///
+/// #[derive(Transaction)]
+/// struct AddPerson<'a> {
+///     // Choose how each step is run (the default is `execute_statement`)
+///     #[transaction(execute_returning_one)]
+///     person: InsertPerson<'a>,
+///     audit: InsertAudit<'a>,
+/// }
+/// ```
+///
+/// It then can be used like so:
+/// ```no_run
+/// # use postgres_named_parameters::{Statement, Transaction};
+/// # #[derive(Statement)]
+/// # #[statement(
+/// #     sql = "INSERT INTO Person (first_name, last_name) VALUES (@first_name, @last_name) RETURNING id",
+/// #     returning = i32
+/// # )]
+/// # struct InsertPerson<'a> {
+/// #     first_name: &'a str,
+/// #     last_name: &'a str,
+/// # }
+/// # #[derive(Statement)]
+/// # #[statement(sql = "INSERT INTO Audit (message) VALUES (@message)")]
+/// # struct InsertAudit<'a> {
+/// #     message: &'a str,
+/// # }
+/// # #[derive(Transaction)]
+/// # struct AddPerson<'a> {
+/// #     #[transaction(execute_returning_one)]
+/// #     person: InsertPerson<'a>,
+/// #     audit: InsertAudit<'a>,
+/// # }
+/// fn main() -> Result<(), postgres_named_parameters::Error> {
+///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///         .unwrap_or("host=localhost user=postgres".to_owned());
+///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
+///
+///     let output = AddPerson {
+///         person: InsertPerson {
+///             first_name: "John",
+///             last_name: "Doe",
+///         },
+///         audit: InsertAudit {
+///             message: "Added John Doe",
+///         },
+///     }
+///     .execute_transaction(&mut db)?;
+///
+///     println!("Inserted person with id {}", output.person);
+///     println!("Inserted {} audit rows", output.audit);
+///     Ok(())
+/// }
+/// ```
+///
+/// # Notes
+/// * The derive macro also generates a struct named after your struct with
+///   `Output` appended (e.g. `AddPersonOutput`), which has a field of the same
+///   name holding the result of each step.
+/// * Each field is run using the method given in its helper attribute
+///   `#[transaction(...)]`, which can be one of `execute_statement` (the
+///   default), `execute_returning_all`, `execute_returning_one`, `query_all`,
+///   `query_opt`, `query_one`, `query_exists` or `query_count`. The
+///   corresponding output field has the same type that the method returns.
+/// * The fields are run in the order they are declared. If any of them fails,
+///   the transaction is rolled back and the error is returned.
+/// * The error is an [Error] by default, so a step's [StatementError] is
+///   converted into an [Error] and its constraint violation is no longer
+///   classified. To keep it, choose the error type using
+///   `#[transaction(error = ...)]` on the struct, e.g.
+///   `#[transaction(error = StatementError<PersonError>)]`. The type must
+///   implement `From` for the error type of every step (and for [Error]).
+/// * The struct may have lifetime parameters but not type parameters.
+pub trait Transaction {
+    /// The struct holding the result of each step, which is generated by the
+    /// derive macro.
+    type Output;
+
+    /// The error returned by the transaction. This is [Error], unless the
+    /// `error` parameter was provided to the derive macro.
+    type Error: From<Error>;
+
+    /// Run each step inside a transaction, and commit it if they all succeed.
+    ///
+    /// For the sole argument you can pass either a database connection (i.e.
+    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]). In
+    /// the latter case, the steps are run inside a savepoint.
+    fn execute_transaction(
         &self,
         connection: &mut impl postgres::GenericClient,
-    ) -> Result<Self::Row, postgres::error::Error>;
+    ) -> Result<Self::Output, Self::Error>;
 }
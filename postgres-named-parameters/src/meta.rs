@@ -0,0 +1,55 @@
+use std::panic::Location;
+
+/// Metadata about a statement or query, for tooling such as tests, registries
+/// and documentation generators. This is implemented by `#[derive(Query)]` and
+/// `#[derive(Statement)]`.
+///
+/// # Example
+/// ```
+/// # use postgres_from_row::FromRow;
+/// # use postgres_named_parameters::{Query, QueryMeta};
+/// # #[derive(FromRow)]
+/// # struct Person {
+/// #     first_name: String,
+/// # }
+/// #[derive(Query)]
+/// #[query(sql = "SELECT * FROM Person WHERE first_name = @name", row = Person)]
+/// struct GetPeople<'a> {
+///     name: &'a str,
+/// }
+///
+/// assert_eq!(GetPeople::NAME, "GetPeople");
+/// // With the `sql-tags` feature, a comment is appended to the SQL
+/// assert!(GetPeople::NUMBERED_SQL.starts_with("SELECT * FROM Person WHERE first_name = $1"));
+/// assert_eq!(GetPeople::PARAMS, &[("name", "&'a str")]);
+/// assert_eq!(GetPeople::ROW_TYPE, Some("Person"));
+/// println!("GetPeople is defined at {}", GetPeople::LOCATION);
+/// ```
+pub trait QueryMeta {
+    /// The name of the struct which derives `Query` or `Statement`.
+    const NAME: &'static str;
+
+    /// The SQL as written in the `sql` parameter, with named parameters (e.g.
+    /// `@name`).
+    const ORIGINAL_SQL: &'static str;
+
+    /// The SQL which is sent to the database, with the named parameters
+    /// replaced by `$1`, `$2`, etc. (and the comment added by the `tag`
+    /// parameter, if any).
+    const NUMBERED_SQL: &'static str;
+
+    /// The name and Rust type of each parameter (i.e. each field of the
+    /// struct), in the order of their numbers. The parameters of tuple structs
+    /// are named `0`, `1`, etc.
+    const PARAMS: &'static [(&'static str, &'static str)];
+
+    /// The Rust type each row decodes to: the `row` parameter of a query, or
+    /// the `returning` parameter of a statement. This is `None` for
+    /// statements which don't return rows.
+    const ROW_TYPE: Option<&'static str>;
+
+    /// Where the struct is defined, i.e. the location of its name. Use
+    /// [file](Location::file) and [line](Location::line) to get the file and
+    /// line.
+    const LOCATION: &'static Location<'static>;
+}
@@ -0,0 +1,89 @@
+use postgres::error::DbError;
+use std::cell::RefCell;
+
+thread_local! {
+    static COLLECTORS: RefCell<Vec<Vec<DbError>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A notice callback which lets [collect_notices] (and methods such as
+/// [query_all_with_notices](crate::Query::query_all_with_notices)) see the
+/// `NOTICE` and `WARNING` messages the database sends, e.g. from `RAISE
+/// NOTICE` in a PL/pgSQL function or from `CREATE TABLE IF NOT EXISTS`.
+///
+/// `postgres` only lets the notice callback be chosen when connecting, so
+/// pass this to [postgres::Config::notice_callback] before calling
+/// [connect](postgres::Config::connect). Notices which aren't being collected
+/// are logged using the `log` crate, just like `postgres` does by default.
+///
+/// # Example
+/// ```no_run
+/// # fn main() -> Result<(), postgres::Error> {
+/// let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///     .unwrap_or("host=localhost user=postgres".to_owned());
+/// let mut db = connection_string
+///     .parse::<postgres::Config>()?
+///     .notice_callback(postgres_named_parameters::notice_callback)
+///     .connect(postgres::NoTls)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn notice_callback(notice: DbError) {
+    // The synchronous client handles messages on the thread which is waiting
+    // for the statement or query, so the notice belongs to that thread
+    let collected = COLLECTORS.with(|collectors| {
+        let mut collectors = collectors.borrow_mut();
+        for collector in collectors.iter_mut() {
+            collector.push(notice.clone());
+        }
+        !collectors.is_empty()
+    });
+    if !collected {
+        log::info!("{}: {}", notice.severity(), notice.message());
+    }
+}
+
+/// Run `f`, returning its result together with the notices (e.g. `NOTICE` and
+/// `WARNING` messages) the database sent while it ran. Use [DbError::severity]
+/// and [DbError::message] to see what each notice says.
+///
+/// Notices are only seen on connections which were created with
+/// [notice_callback] (see its docs). If calls are nested, the notices are
+/// returned by each of them.
+///
+/// # Example
+/// ```no_run
+/// # use postgres_named_parameters::{collect_notices, Statement};
+/// #[derive(Statement)]
+/// #[statement(sql = "CREATE TABLE IF NOT EXISTS Person (id SERIAL PRIMARY KEY)")]
+/// struct CreatePersonTable;
+///
+/// # fn main() -> Result<(), postgres_named_parameters::Error> {
+/// # let mut db = "host=localhost user=postgres"
+/// #     .parse::<postgres::Config>()?
+/// #     .notice_callback(postgres_named_parameters::notice_callback)
+/// #     .connect(postgres::NoTls)?;
+/// let (result, notices) = collect_notices(|| CreatePersonTable.execute_statement(&mut db));
+/// result?;
+/// for notice in notices {
+///     // e.g. NOTICE: relation "person" already exists, skipping
+///     println!("{}: {}", notice.severity(), notice.message());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn collect_notices<T>(f: impl FnOnce() -> T) -> (T, Vec<DbError>) {
+    // Stop collecting even if `f` panics
+    struct Guard;
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            COLLECTORS.with(|collectors| collectors.borrow_mut().pop());
+        }
+    }
+
+    COLLECTORS.with(|collectors| collectors.borrow_mut().push(Vec::new()));
+    let guard = Guard;
+    let result = f();
+    std::mem::forget(guard);
+    let notices = COLLECTORS.with(|collectors| collectors.borrow_mut().pop());
+    (result, notices.unwrap_or_default())
+}
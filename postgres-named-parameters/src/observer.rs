@@ -0,0 +1,140 @@
+use crate::Error;
+use std::cell::RefCell;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// An observer which is told about each statement and query that is run, e.g.
+/// to record metrics, log slow queries or keep an audit trail.
+///
+/// The observer is called by every method of [Query](crate::Query),
+/// [PaginatedQuery](crate::PaginatedQuery), [Statement](crate::Statement),
+/// [ReturningStatement](crate::ReturningStatement),
+/// [BulkInsert](crate::BulkInsert) and [Explain](crate::Explain) implemented
+/// by the derive macros. Install it for the whole program using [set_query_observer], or
+/// for part of it using [with_query_observer].
+///
+/// # Example
+/// ```
+/// # use postgres_named_parameters::{Error, QueryInfo, QueryObserver};
+/// # use std::time::Duration;
+/// struct SlowQueryLogger;
+///
+/// impl QueryObserver for SlowQueryLogger {
+///     fn after(&self, query: &QueryInfo, duration: Duration, result: Result<u64, &Error>) {
+///         if duration > Duration::from_secs(1) {
+///             println!("{} took {:?}: {}", query.name, duration, query.sql);
+///         }
+///         if let Err(err) = result {
+///             println!("{} failed: {}", query.name, err);
+///         }
+///     }
+/// }
+///
+/// postgres_named_parameters::set_query_observer(SlowQueryLogger);
+/// ```
+pub trait QueryObserver: Send + Sync {
+    /// Called before the statement or query is run. Does nothing by default.
+    fn before(&self, query: &QueryInfo) {
+        let _ = query;
+    }
+
+    /// Called after the statement or query has run, with how long it took and
+    /// either the number of rows it returned or affected, or the error it
+    /// failed with.
+    fn after(&self, query: &QueryInfo, duration: Duration, result: Result<u64, &Error>);
+}
+
+/// Which statement or query a [QueryObserver] is being told about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryInfo {
+    /// The name of the struct which derives `Query` or `Statement`.
+    pub name: &'static str,
+    /// The method that was called, e.g. `query_all` or `execute_statement`.
+    pub method: &'static str,
+    /// The SQL that was run, with the named parameters replaced by `$1`, `$2`,
+    /// etc.
+    pub sql: &'static str,
+}
+
+static GLOBAL_OBSERVER: RwLock<Option<Arc<dyn QueryObserver>>> = RwLock::new(None);
+
+thread_local! {
+    static SCOPED_OBSERVERS: RefCell<Vec<Arc<dyn QueryObserver>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Install an observer which is told about every statement and query run by
+/// any thread, replacing any observer installed previously. Observers
+/// installed using [with_query_observer] take precedence.
+pub fn set_query_observer(observer: impl QueryObserver + 'static) {
+    *GLOBAL_OBSERVER.write().unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(observer));
+}
+
+/// Remove the observer installed using [set_query_observer], if any.
+pub fn clear_query_observer() {
+    *GLOBAL_OBSERVER.write().unwrap_or_else(|err| err.into_inner()) = None;
+}
+
+/// Run `f`, telling the given observer (instead of the one installed using
+/// [set_query_observer]) about the statements and queries it runs on the
+/// current thread.
+///
+/// This is useful for observing a single client or request: the `postgres`
+/// connection types can't be wrapped (their `GenericClient` trait is sealed),
+/// so wrap the code which uses the client instead.
+///
+/// ```no_run
+/// # use postgres_named_parameters::{with_query_observer, Error, QueryInfo, QueryObserver};
+/// # use std::time::Duration;
+/// # struct AuditLog { user: String }
+/// # impl QueryObserver for AuditLog {
+/// #     fn after(&self, query: &QueryInfo, _: Duration, _: Result<u64, &Error>) {}
+/// # }
+/// # fn handle_request(_: &mut postgres::Client) -> Result<(), Error> { Ok(()) }
+/// # let mut db = postgres::Client::connect("host=localhost user=postgres", postgres::NoTls).unwrap();
+/// let audit_log = AuditLog { user: "alice".to_owned() };
+/// with_query_observer(audit_log, || handle_request(&mut db))?;
+/// # Ok::<(), Error>(())
+/// ```
+pub fn with_query_observer<T>(observer: impl QueryObserver + 'static, f: impl FnOnce() -> T) -> T {
+    // Remove the observer again even if `f` panics
+    struct Guard;
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            SCOPED_OBSERVERS.with(|observers| observers.borrow_mut().pop());
+        }
+    }
+
+    SCOPED_OBSERVERS.with(|observers| observers.borrow_mut().push(Arc::new(observer)));
+    let _guard = Guard;
+    f()
+}
+
+/// The observer to tell about a statement or query run on the current thread.
+fn current_observer() -> Option<Arc<dyn QueryObserver>> {
+    SCOPED_OBSERVERS
+        .with(|observers| observers.borrow().last().cloned())
+        .or_else(|| {
+            GLOBAL_OBSERVER
+                .read()
+                .unwrap_or_else(|err| err.into_inner())
+                .clone()
+        })
+}
+
+/// Run `f`, telling the current observer (if any) about it. Used by
+/// `#[derive(Query)]` and `#[derive(Statement)]`.
+pub fn observe<T>(
+    query: QueryInfo,
+    rows: impl FnOnce(&T) -> u64,
+    f: impl FnOnce() -> Result<T, Error>,
+) -> Result<T, Error> {
+    let Some(observer) = current_observer() else {
+        return f();
+    };
+    observer.before(&query);
+    let start = Instant::now();
+    let result = f();
+    let duration = start.elapsed();
+    observer.after(&query, duration, result.as_ref().map(rows));
+    result
+}
@@ -0,0 +1,451 @@
+//! Pagination of the rows returned by a [Query](crate::Query). See
+//! [PaginatedQuery] for details.
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use bytes::BytesMut;
+use postgres::types::{FromSql, IsNull, Oid, ToSql, Type};
+use crate::internal::wrapper_for_derive_macro::try_from_row;
+use std::error::Error;
+
+/// A `PaginatedQuery` is a [Query](crate::Query) whose rows can be fetched one
+/// page at a time.
+///
+/// # Example
+/// `PaginatedQuery` is implemented by `#[derive(Query)]` when you provide the
+/// `paginate` parameter:
+/// ```no_run
+/// # use postgres_from_row::FromRow;
+/// # use postgres_named_parameters::Query;
+/// #[derive(FromRow, Debug)]
+/// struct Person {
+///     id: i32,
+///     first_name: String,
+///     created_at: std::time::SystemTime,
+/// }
+///
+/// #[derive(Query)]
+/// #[query(
+///     sql = "SELECT * FROM Person WHERE alive = @alive",
+///     row = Person,
+///     // Order the rows by these columns, and start each page after the last
+///     // row of the previous page
+///     paginate = keyset(order_by = "created_at, id", total)
+/// )]
+/// struct GetPeople {
+///     alive: bool,
+/// }
+/// ```
+///
+/// It then can be used like so:
+/// ```no_run
+/// # use postgres_from_row::FromRow;
+/// # use postgres_named_parameters::{PaginatedQuery, Query};
+/// # #[derive(FromRow, Debug)]
+/// # struct Person {
+/// #     id: i32,
+/// #     first_name: String,
+/// #     created_at: std::time::SystemTime,
+/// # }
+/// # #[derive(Query)]
+/// # #[query(
+/// #     sql = "SELECT * FROM Person WHERE alive = @alive",
+/// #     row = Person,
+/// #     paginate = keyset(order_by = "created_at, id", total)
+/// # )]
+/// # struct GetPeople {
+/// #     alive: bool,
+/// # }
+/// fn main() -> Result<(), postgres_named_parameters::Error> {
+///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///         .unwrap_or("host=localhost user=postgres".to_owned());
+///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
+///
+///     let query = GetPeople { alive: true };
+///     let mut cursor = None;
+///     loop {
+///         let page = query.query_page(&mut db, cursor.as_ref(), 50)?;
+///         println!("Found {} of {:?} people", page.rows.len(), page.total);
+///         match page.next_cursor {
+///             Some(next_cursor) => cursor = Some(next_cursor),
+///             None => break,
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+/// At compile time, the SQL is wrapped in a query which orders and limits the
+/// rows. For example, the above query fetches each page after the first using:
+/// ```sql
+/// SELECT * FROM (SELECT * FROM Person WHERE alive = $1) _page
+/// WHERE (created_at, id) > ($2, $3)
+/// ORDER BY created_at, id
+/// LIMIT $4
+/// ```
+///
+/// # Notes
+/// * The `paginate` parameter can be one of:
+///     * `keyset(order_by = "...")`: Order the rows by the given comma
+///       separated list of columns, and fetch the rows after the last row of
+///       the previous page. The columns must be returned by the query (i.e. use
+///       `created_at` rather than `Person.created_at`), must not be `NULL`, and
+///       must uniquely identify each row (e.g. by ending with a primary key).
+///       Rows are ordered in ascending order, since the rows after the cursor
+///       are found by comparing the columns as a tuple, so each column must be
+///       a plain column name (`ASC`, `DESC` or expressions are a compile-time
+///       error). The cursor is a [KeysetCursor].
+///     * `offset(order_by = "...")`: Order the rows by the given comma
+///       separated list of columns (which, as for `keyset`, must be returned by
+///       the query and should uniquely identify each row), and skip the rows of
+///       the previous pages using `OFFSET`. This is simpler than `keyset` but
+///       slower for later pages, and rows can be skipped or repeated if they
+///       are inserted or deleted between fetching pages. The columns can
+///       include `ASC` or `DESC`. The cursor is an [OffsetCursor].
+///     * `offset`: Like `offset(order_by = "...")`, but relying on an
+///       `ORDER BY` clause in your SQL. Since your SQL becomes a subquery,
+///       Postgres doesn't guarantee that its order is kept (though in practice
+///       it is for simple queries), so prefer `offset(order_by = "...")`.
+/// * Add `total` (e.g. `keyset(order_by = "id", total)` or `offset(total)`) to
+///   also count the total number of rows (across all pages) using
+///   [query_count](crate::Query::query_count), which is returned in
+///   [Page::total].
+pub trait PaginatedQuery: crate::Query {
+    /// The type of cursor that marks where the next page starts. This is
+    /// either [KeysetCursor] or [OffsetCursor] depending on the `paginate`
+    /// parameter.
+    type Cursor;
+
+    /// The fingerprints (see [sql_fingerprint](crate::sql_fingerprint)) of
+    /// the SQL run by [query_page](PaginatedQuery::query_page), which wraps
+    /// the query's SQL and so is recorded separately by `pg_stat_statements`.
+    /// Keyset pagination runs one query for the first page and another for
+    /// the pages after it, so it has two.
+    const PAGE_FINGERPRINTS: &'static [u64];
+
+    /// Run the query and return a page of at most `limit` rows, starting at the
+    /// given cursor. Pass `None` to fetch the first page, and then pass the
+    /// [next_cursor](Page::next_cursor) of each page to fetch the page after
+    /// it. A `limit` of zero or less returns an empty page with no next
+    /// cursor.
+    ///
+    /// For the first argument you can pass either a database connection (i.e.
+    /// [postgres::Client]) or a transaction (i.e. [postgres::Transaction]),
+    /// unless the `requires_transaction` parameter was provided (see
+    /// [Connection](crate::Connection)).
+    fn query_page(
+        &self,
+        connection: &mut impl crate::Connection<Self::Requires>,
+        cursor: Option<&Self::Cursor>,
+        limit: i64,
+    ) -> Result<Page<Self::Row, Self::Cursor>, crate::Error>;
+}
+
+/// A page of rows returned by [query_page](PaginatedQuery::query_page).
+#[derive(Debug, Clone)]
+pub struct Page<Row, Cursor> {
+    /// The rows in this page.
+    pub rows: Vec<Row>,
+    /// The cursor to pass to [query_page](PaginatedQuery::query_page) to fetch
+    /// the next page, or `None` if this is the last page.
+    pub next_cursor: Option<Cursor>,
+    /// The total number of rows across all pages, if `total` was specified in
+    /// the `paginate` parameter.
+    pub total: Option<i64>,
+}
+
+/// The cursor of a [PaginatedQuery] using `paginate = offset`, which holds the
+/// number of rows in the previous pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffsetCursor(pub i64);
+
+/// The cursor of a [PaginatedQuery] using `paginate = keyset(...)`, which holds
+/// the values of the `order_by` columns in the last row of the previous page.
+///
+/// The values are held in their binary encoding, so this cursor can only be
+/// used with the query which returned it. Use [to_token](KeysetCursor::to_token)
+/// and [from_token](KeysetCursor::from_token) to pass it to and from a client
+/// (e.g. as the `cursor` parameter of a list endpoint).
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeysetCursor {
+    values: Vec<CursorValue>,
+}
+
+/// The version of the format written by [KeysetCursor::to_token].
+const TOKEN_VERSION: u8 = 1;
+
+impl KeysetCursor {
+    /// Encode the cursor as an opaque, URL-safe string, which
+    /// [from_token](KeysetCursor::from_token) decodes again.
+    ///
+    /// The token isn't encrypted or signed: whoever holds it can decode the
+    /// values of the `order_by` columns in it, and can change them (though only
+    /// to other values of the same types). Don't paginate by columns whose
+    /// values must be kept secret.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use postgres_from_row::FromRow;
+    /// # use postgres_named_parameters::{KeysetCursor, PaginatedQuery, Query};
+    /// # #[derive(FromRow, Debug)]
+    /// # struct Person {
+    /// #     id: i32,
+    /// #     first_name: String,
+    /// # }
+    /// # #[derive(Query)]
+    /// # #[query(
+    /// #     sql = "SELECT * FROM Person",
+    /// #     row = Person,
+    /// #     paginate = keyset(order_by = "id")
+    /// # )]
+    /// # struct GetPeople;
+    /// // e.g. the handler of `GET /people?cursor=...`, which returns a page of
+    /// // people and the cursor to request the next page with
+    /// fn list_people(
+    ///     db: &mut postgres::Client,
+    ///     cursor: Option<&str>,
+    /// ) -> Result<(Vec<Person>, Option<String>), Box<dyn std::error::Error>> {
+    ///     let cursor = cursor.map(KeysetCursor::from_token).transpose()?;
+    ///     let page = GetPeople.query_page(db, cursor.as_ref(), 50)?;
+    ///     let next_cursor = page.next_cursor.map(|cursor| cursor.to_token());
+    ///     Ok((page.rows, next_cursor))
+    /// }
+    /// ```
+    pub fn to_token(&self) -> String {
+        let mut bytes = vec![TOKEN_VERSION];
+        for value in &self.values {
+            bytes.extend_from_slice(&value.oid.to_be_bytes());
+            match &value.raw {
+                Some(raw) => {
+                    bytes.extend_from_slice(&(raw.len() as i32).to_be_bytes());
+                    bytes.extend_from_slice(raw);
+                }
+                None => bytes.extend_from_slice(&(-1i32).to_be_bytes()),
+            }
+        }
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    /// Decode a cursor encoded by [to_token](KeysetCursor::to_token).
+    pub fn from_token(token: &str) -> Result<KeysetCursor, InvalidCursorToken> {
+        let bytes = URL_SAFE_NO_PAD.decode(token).map_err(|_| InvalidCursorToken)?;
+        let Some((&TOKEN_VERSION, mut bytes)) = bytes.split_first() else {
+            return Err(InvalidCursorToken);
+        };
+        let mut values = vec![];
+        while !bytes.is_empty() {
+            let oid = Oid::from_be_bytes(take(&mut bytes)?);
+            let raw = match i32::from_be_bytes(take(&mut bytes)?) {
+                -1 => None,
+                length => {
+                    let length = usize::try_from(length).map_err(|_| InvalidCursorToken)?;
+                    let (raw, rest) = bytes.split_at_checked(length).ok_or(InvalidCursorToken)?;
+                    bytes = rest;
+                    Some(raw.to_vec())
+                }
+            };
+            values.push(CursorValue { oid, raw });
+        }
+        if values.is_empty() {
+            return Err(InvalidCursorToken);
+        }
+        Ok(KeysetCursor { values })
+    }
+}
+
+/// Remove the first `N` bytes of a token.
+fn take<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], InvalidCursorToken> {
+    let (taken, rest) = bytes.split_first_chunk::<N>().ok_or(InvalidCursorToken)?;
+    *bytes = rest;
+    Ok(*taken)
+}
+
+/// The error returned by [KeysetCursor::from_token] when the token wasn't
+/// returned by [KeysetCursor::to_token] (e.g. because it was cut short).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidCursorToken;
+
+impl std::fmt::Display for InvalidCursorToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid keyset cursor token")
+    }
+}
+
+impl Error for InvalidCursorToken {}
+
+/// A single value of a [KeysetCursor], which is passed back to Postgres exactly
+/// as it was received. The type is held as its OID, so that cursors decoded
+/// from tokens can hold values of types which aren't built in (e.g. enums).
+#[derive(Debug, Clone, PartialEq)]
+struct CursorValue {
+    oid: Oid,
+    raw: Option<Vec<u8>>,
+}
+
+impl<'a> FromSql<'a> for CursorValue {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Ok(CursorValue {
+            oid: ty.oid(),
+            raw: Some(raw.to_vec()),
+        })
+    }
+
+    fn from_sql_null(ty: &Type) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Ok(CursorValue {
+            oid: ty.oid(),
+            raw: None,
+        })
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+}
+
+impl ToSql for CursorValue {
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        if ty.oid() != self.oid {
+            let cursor_type = match Type::from_oid(self.oid) {
+                Some(cursor_type) => cursor_type.to_string(),
+                None => format!("OID {}", self.oid),
+            };
+            return Err(format!(
+                "the keyset cursor holds a value of type {} where a value of type {} was expected; a cursor can only be used with the query which returned it",
+                cursor_type, ty,
+            )
+            .into());
+        }
+        match &self.raw {
+            Some(raw) => {
+                out.extend_from_slice(raw);
+                Ok(IsNull::No)
+            }
+            None => Ok(IsNull::Yes),
+        }
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+
+    postgres::types::to_sql_checked!();
+}
+
+/// Fetch a page of a query using `paginate = keyset(...)`. Used by
+/// `#[derive(Query)]`.
+pub fn query_keyset_page<T: crate::DecodeRow<Kind>, Kind>(
+    connection: &mut impl postgres::GenericClient,
+    first_page_sql: &str,
+    next_page_sql: &str,
+    parameters: &[&(dyn ToSql + Sync)],
+    order_by: &[&str],
+    cursor: Option<&KeysetCursor>,
+    limit: i64,
+) -> Result<Page<T, KeysetCursor>, crate::Error> {
+    let mut parameters = parameters.to_vec();
+    let sql = match cursor {
+        None => first_page_sql,
+        Some(cursor) => {
+            parameters.extend(cursor.values.iter().map(|value| value as &(dyn ToSql + Sync)));
+            next_page_sql
+        }
+    };
+    // Fetch one extra row to find out whether there is a next page
+    let limit = limit.max(0);
+    let fetch_limit = limit.saturating_add(1);
+    parameters.push(&fetch_limit);
+    let rows = connection.query(sql, &parameters)?;
+
+    let has_next_page = rows.len() as i64 > limit;
+    let rows = &rows[..rows.len().min(limit as usize)];
+    let next_cursor = match rows.last() {
+        Some(last_row) if has_next_page => {
+            let values = order_by
+                .iter()
+                .map(|column| {
+                    last_row
+                        .try_get::<_, CursorValue>(*column)
+                        .map_err(|err| crate::Error::decode(err, Some(column)))
+                })
+                .collect::<Result<_, _>>()?;
+            Some(KeysetCursor { values })
+        }
+        _ => None,
+    };
+    Ok(Page {
+        rows: rows.iter().map(try_from_row).collect::<Result<_, _>>()?,
+        next_cursor,
+        total: None,
+    })
+}
+
+/// Fetch a page of a query using `paginate = offset`. Used by
+/// `#[derive(Query)]`.
+pub fn query_offset_page<T: crate::DecodeRow<Kind>, Kind>(
+    connection: &mut impl postgres::GenericClient,
+    sql: &str,
+    parameters: &[&(dyn ToSql + Sync)],
+    cursor: Option<&OffsetCursor>,
+    limit: i64,
+) -> Result<Page<T, OffsetCursor>, crate::Error> {
+    let mut parameters = parameters.to_vec();
+    // Fetch one extra row to find out whether there is a next page
+    let limit = limit.max(0);
+    let fetch_limit = limit.saturating_add(1);
+    let offset = cursor.map_or(0, |cursor| cursor.0);
+    parameters.push(&fetch_limit);
+    parameters.push(&offset);
+    let rows = connection.query(sql, &parameters)?;
+
+    // An empty page (i.e. a limit of 0) has no next page, since fetching it
+    // would return the same empty page forever
+    let has_next_page = rows.len() as i64 > limit && limit > 0;
+    let rows = &rows[..rows.len().min(limit as usize)];
+    Ok(Page {
+        rows: rows.iter().map(try_from_row).collect::<Result<_, _>>()?,
+        next_cursor: has_next_page.then(|| OffsetCursor(offset.saturating_add(limit))),
+        total: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor() -> KeysetCursor {
+        KeysetCursor {
+            values: vec![
+                CursorValue {
+                    oid: Type::INT4.oid(),
+                    raw: Some(42i32.to_be_bytes().to_vec()),
+                },
+                CursorValue {
+                    oid: Type::TEXT.oid(),
+                    raw: Some(b"O'Brien".to_vec()),
+                },
+                CursorValue {
+                    oid: 16_385,
+                    raw: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn tokens_round_trip() {
+        let token = cursor().to_token();
+        assert!(token
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_'));
+        assert_eq!(KeysetCursor::from_token(&token), Ok(cursor()));
+    }
+
+    #[test]
+    fn invalid_tokens_are_rejected() {
+        let token = cursor().to_token();
+        assert_eq!(KeysetCursor::from_token(""), Err(InvalidCursorToken));
+        assert_eq!(KeysetCursor::from_token("not a token!"), Err(InvalidCursorToken));
+        assert_eq!(KeysetCursor::from_token(&token[..token.len() - 4]), Err(InvalidCursorToken));
+        // A token with a newer version, or with no values
+        assert_eq!(KeysetCursor::from_token(&URL_SAFE_NO_PAD.encode([2])), Err(InvalidCursorToken));
+        assert_eq!(KeysetCursor::from_token(&URL_SAFE_NO_PAD.encode([1])), Err(InvalidCursorToken));
+    }
+}
@@ -0,0 +1,21 @@
+use crate::Connection;
+
+/// Run `f` inside a read-only transaction. Used by `#[derive(Query)]` when the
+/// `read_only` parameter is provided.
+///
+/// This starts a transaction if the connection is a [postgres::Client], or a
+/// savepoint if it is already a transaction, and makes it read-only using
+/// `SET TRANSACTION READ ONLY`. It is then rolled back rather than committed:
+/// nothing was written, and inside a savepoint this undoes the `SET
+/// TRANSACTION READ ONLY`, which would otherwise last until the enclosing
+/// transaction ends.
+pub fn with_read_only<C: Connection<Requires>, Requires, T, E: From<postgres::Error>>(
+    connection: &mut C,
+    f: impl FnOnce(&mut postgres::Transaction<'_>) -> Result<T, E>,
+) -> Result<T, E> {
+    let mut transaction = connection.transaction()?;
+    transaction.batch_execute("SET TRANSACTION READ ONLY")?;
+    let result = f(&mut transaction)?;
+    transaction.rollback()?;
+    Ok(result)
+}
@@ -0,0 +1,328 @@
+use crate::{DecodeRow, RowColumns};
+use postgres::types::Type;
+use postgres::Column;
+use std::marker::PhantomData;
+#[cfg(feature = "registry")]
+use std::panic::Location;
+
+/// Checks used by `validate_all` which `#[derive(Query)]` and
+/// `#[derive(Statement)]` implement alongside [QueryMeta](crate::QueryMeta).
+pub trait Validate {
+    /// Check that the fields can be passed as parameters of the given types.
+    fn check_parameters(types: &[Type]) -> Vec<String>;
+
+    /// Check that rows with the given columns can be decoded into the row
+    /// type, if any.
+    fn check_columns(columns: &[Column]) -> Vec<String>;
+}
+
+/// A parameter's name, Rust type and the `accepts` function of its
+/// [ToSql](postgres::types::ToSql) implementation.
+type Parameter<'a> = (&'a str, &'a str, fn(&Type) -> bool);
+
+/// Check that each parameter, given as its name, Rust type and the `accepts`
+/// function of its [ToSql](postgres::types::ToSql) implementation, can be
+/// passed as the parameter of the same number with the given type. Used by
+/// `#[derive(Query)]` and `#[derive(Statement)]` to implement [Validate].
+pub fn check_parameters(
+    types: &[Type],
+    parameters: &[Parameter<'_>],
+) -> Vec<String> {
+    let mut problems = vec![];
+    if types.len() != parameters.len() {
+        problems.push(format!(
+            "the SQL has {} parameters, but the struct has {} fields",
+            types.len(),
+            parameters.len(),
+        ));
+    }
+    for (index, (ty, (name, rust_type, accepts))) in types.iter().zip(parameters).enumerate() {
+        if !accepts(ty) {
+            problems.push(format!(
+                "parameter `{}` (${}) has type {}, which `{}` can't be converted to",
+                name,
+                index + 1,
+                ty,
+                rust_type,
+            ));
+        }
+    }
+    problems
+}
+
+/// Checks that rows with the given columns can be decoded into `T`. Used by
+/// `#[derive(Query)]` and `#[derive(Statement)]` to implement [Validate].
+///
+/// Struct row types implementing [RowColumns] are checked using it, and other
+/// row types using [DecodeRow::check_columns]. Since the generated code names
+/// the row type, this is chosen by method resolution ("autoref
+/// specialization"): calling `(&&ColumnChecker::<T>::new()).check_columns(..)`
+/// finds [CheckRowColumns] (implemented for `&ColumnChecker<T>`) first, if
+/// `T` implements [RowColumns], and [CheckDecodeRow] (implemented for
+/// `ColumnChecker<T>`) otherwise.
+pub struct ColumnChecker<T>(PhantomData<T>);
+
+impl<T> ColumnChecker<T> {
+    /// Create a checker for the row type `T`.
+    pub fn new() -> ColumnChecker<T> {
+        ColumnChecker(PhantomData)
+    }
+}
+
+impl<T> Default for ColumnChecker<T> {
+    fn default() -> ColumnChecker<T> {
+        ColumnChecker::new()
+    }
+}
+
+/// See [ColumnChecker].
+pub trait CheckRowColumns {
+    /// Check the columns using [RowColumns::check_columns].
+    fn check_columns(&self, columns: &[Column]) -> Vec<String>;
+}
+
+impl<T: RowColumns> CheckRowColumns for &ColumnChecker<T> {
+    fn check_columns(&self, columns: &[Column]) -> Vec<String> {
+        T::check_columns(columns)
+    }
+}
+
+/// See [ColumnChecker].
+pub trait CheckDecodeRow<Kind> {
+    /// Check the columns using [DecodeRow::check_columns].
+    fn check_columns(&self, columns: &[Column]) -> Vec<String>;
+}
+
+impl<T: DecodeRow<Kind>, Kind> CheckDecodeRow<Kind> for ColumnChecker<T> {
+    fn check_columns(&self, columns: &[Column]) -> Vec<String> {
+        T::check_columns(columns)
+    }
+}
+
+/// A statement or query registered by `#[derive(Query)]` or
+/// `#[derive(Statement)]` when the `registry` feature is enabled. See
+/// [registered_queries].
+///
+/// The fields are the same as the associated constants of
+/// [QueryMeta](crate::QueryMeta).
+#[cfg(feature = "registry")]
+#[derive(Debug)]
+pub struct RegisteredQuery {
+    /// The name of the struct which derives `Query` or `Statement`.
+    pub name: &'static str,
+    /// The SQL as written in the `sql` parameter.
+    pub original_sql: &'static str,
+    /// The SQL which is sent to the database.
+    pub numbered_sql: &'static str,
+    /// The fingerprint of the SQL (see [sql_fingerprint](crate::sql_fingerprint)).
+    pub fingerprint: u64,
+    /// The name and Rust type of each parameter.
+    pub params: &'static [(&'static str, &'static str)],
+    /// The Rust type each row decodes to, if any.
+    pub row_type: Option<&'static str>,
+    /// Where the struct is defined.
+    pub location: &'static Location<'static>,
+    check_parameters: fn(&[Type]) -> Vec<String>,
+    check_columns: fn(&[Column]) -> Vec<String>,
+}
+
+#[cfg(feature = "registry")]
+impl RegisteredQuery {
+    #[doc(hidden)]
+    pub const fn new<T: crate::QueryMeta + Validate>() -> RegisteredQuery {
+        RegisteredQuery {
+            name: T::NAME,
+            original_sql: T::ORIGINAL_SQL,
+            numbered_sql: T::NUMBERED_SQL,
+            fingerprint: crate::sql_fingerprint(T::NUMBERED_SQL),
+            params: T::PARAMS,
+            row_type: T::ROW_TYPE,
+            location: T::LOCATION,
+            check_parameters: T::check_parameters,
+            check_columns: T::check_columns,
+        }
+    }
+
+    /// Prepare the statement or query and check it against its struct,
+    /// returning a description of each problem.
+    fn validate(&self, client: &mut postgres::Client) -> Vec<String> {
+        match client.prepare(self.numbered_sql) {
+            Ok(statement) => {
+                let mut problems = (self.check_parameters)(statement.params());
+                problems.extend((self.check_columns)(statement.columns()));
+                problems
+            }
+            Err(err) => vec![crate::Error::from(err).to_string()],
+        }
+    }
+}
+
+#[cfg(feature = "registry")]
+inventory::collect!(RegisteredQuery);
+
+/// Register a statement or query so that `validate_all` checks it. Used by
+/// `#[derive(Query)]` and `#[derive(Statement)]`.
+///
+/// This is a macro because the derive macros can't tell whether the
+/// `registry` feature is enabled.
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! register_for_derive_macro {
+    ($ty:ty) => {
+        $crate::internal::registry_for_derive_macro::inventory::submit! {
+            $crate::RegisteredQuery::new::<$ty>()
+        }
+    };
+}
+
+/// Register a statement or query so that `validate_all` checks it. This does
+/// nothing, since the `registry` feature is disabled.
+#[cfg(not(feature = "registry"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! register_for_derive_macro {
+    ($ty:ty) => {};
+}
+
+/// Every statement and query in the program (including its dependencies)
+/// which derives `Query` or `Statement`, sorted by name. Requires the
+/// `registry` feature.
+///
+/// Structs with type parameters aren't registered.
+#[cfg(feature = "registry")]
+pub fn registered_queries() -> Vec<&'static RegisteredQuery> {
+    let mut queries: Vec<_> = inventory::iter::<RegisteredQuery>.into_iter().collect();
+    queries.sort_by_key(|query| (query.name, query.location.file(), query.location.line()));
+    queries
+}
+
+/// An SQL catalog of every registered statement and query (see
+/// [registered_queries]), so that the SQL the program can run can be reviewed
+/// in one place. Each statement or query is preceded by comments giving its
+/// name, location and fingerprint. Requires the `registry` feature.
+///
+/// The catalog is built at run time rather than at build time: the
+/// statements and queries are only collected once the program is linked, and
+/// a build script runs before the crate defining them is compiled. Write it
+/// to a file from a test (as below) to keep a copy in the repository.
+///
+/// # Example
+/// ```no_run
+/// // e.g. in a test, so that changes to the catalog show up in code review
+/// let catalog = postgres_named_parameters::sql_catalog();
+/// std::fs::write("queries.sql", catalog).unwrap();
+/// ```
+#[cfg(feature = "registry")]
+pub fn sql_catalog() -> String {
+    registered_queries()
+        .into_iter()
+        .map(|query| {
+            format!(
+                "-- name: {}\n-- location: {}:{}\n-- fingerprint: {:016x}\n{};\n",
+                query.name,
+                query.location.file(),
+                query.location.line(),
+                query.fingerprint,
+                query.numbered_sql.trim().trim_end_matches(';'),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Check every registered statement and query (see [registered_queries])
+/// against the database, so that a schema which has drifted from the code is
+/// caught when the program starts rather than when the query is first run.
+/// Requires the `registry` feature.
+///
+/// Each statement and query is prepared (but not run), and the following
+/// are checked:
+/// * The SQL is valid and refers to tables and columns which exist
+/// * The SQL has one parameter per field, and the type Postgres infers for
+///   each parameter can be converted from the field's Rust type
+/// * The columns returned can be decoded into the `row` (or `returning`) type.
+///   Struct row types are only checked if they derive
+///   [RowColumns](crate::RowColumns) as well as
+///   [FromRow](postgres_from_row::FromRow), which doesn't say which columns a
+///   struct needs. Otherwise mismatched columns are only reported when the
+///   query is run
+///
+/// Every problem is reported, rather than just the first.
+///
+/// # Example
+/// ```no_run
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///         .unwrap_or("host=localhost user=postgres".to_owned());
+///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
+///
+///     // Fail now if any statement or query doesn't match the database
+///     postgres_named_parameters::validate_all(&mut db)?;
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "registry")]
+pub fn validate_all(client: &mut postgres::Client) -> Result<(), ValidationError> {
+    let mut mismatches = vec![];
+    for query in registered_queries() {
+        for message in query.validate(client) {
+            mismatches.push(Mismatch { query, message });
+        }
+    }
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError { mismatches })
+    }
+}
+
+/// The error returned by [validate_all], listing every problem it found.
+#[cfg(feature = "registry")]
+#[derive(Debug)]
+pub struct ValidationError {
+    mismatches: Vec<Mismatch>,
+}
+
+/// A problem found by [validate_all].
+#[cfg(feature = "registry")]
+#[derive(Debug)]
+pub struct Mismatch {
+    /// The statement or query which doesn't match the database.
+    pub query: &'static RegisteredQuery,
+    /// What doesn't match.
+    pub message: String,
+}
+
+#[cfg(feature = "registry")]
+impl ValidationError {
+    /// Every problem found, in the order of [registered_queries].
+    pub fn mismatches(&self) -> &[Mismatch] {
+        &self.mismatches
+    }
+}
+
+#[cfg(feature = "registry")]
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} problems found checking statements and queries against the database:",
+            self.mismatches.len()
+        )?;
+        for mismatch in &self.mismatches {
+            write!(
+                f,
+                "\n* {} ({}:{}): {}",
+                mismatch.query.name,
+                mismatch.query.location.file(),
+                mismatch.query.location.line(),
+                mismatch.message,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "registry")]
+impl std::error::Error for ValidationError {}
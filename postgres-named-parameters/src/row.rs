@@ -0,0 +1,172 @@
+//! Decoding of the rows returned by a [Query](crate::Query) or a
+//! [ReturningStatement](crate::ReturningStatement).
+use postgres::types::{FromSql, FromSqlOwned};
+use postgres::Column;
+
+/// A type that each row returned from the database can be decoded to.
+///
+/// You should not need to implement this trait yourself, as it is already
+/// implemented for:
+/// * Any struct implementing [FromRow](postgres_from_row::FromRow), which you
+///   can get by using `#[derive(FromRow)]` from
+///   [postgres-from-row](https://crates.io/crates/postgres-from-row). Each
+///   column is decoded into the field of the same name.
+/// * Any scalar type implementing [FromSql](postgres::types::FromSql), such as
+///   `i64`, `String` or `Option<bool>`. The first column is decoded and any
+///   other columns are ignored.
+/// * Tuples of up to 16 elements that each implement
+///   [FromSql](postgres::types::FromSql), such as `(i32, String)`. The first
+///   element is decoded from the first column, the second element from the
+///   second column, and so on.
+///
+/// The `Kind` parameter ([Struct], [Scalar] or [Tuple]) only exists to keep
+/// these implementations from overlapping, and is inferred automatically.
+/// (This means a type implementing both `FromRow` and `FromSql` cannot be
+/// used as a row type, since it would be ambiguous how to decode it.)
+pub trait DecodeRow<Kind>: Sized {
+    /// Decode a single row returned from the database, returning a
+    /// [Decode](crate::ErrorKind::Decode) error if it can't be decoded.
+    fn decode_row(row: &postgres::Row) -> Result<Self, crate::Error>;
+
+    /// Check that rows with the given columns can be decoded into this type,
+    /// returning a description of each problem. Used by `validate_all` (see
+    /// the `registry` feature).
+    ///
+    /// Struct row types aren't checked here, since
+    /// [FromRow](postgres_from_row::FromRow) doesn't say which columns it
+    /// needs. Derive [RowColumns] for them as well to have them checked.
+    fn check_columns(columns: &[Column]) -> Vec<String> {
+        let _ = columns;
+        Vec::new()
+    }
+}
+
+/// A struct row type which says which columns it is decoded from, so that
+/// `validate_all` (see the `registry` feature) can check that each query
+/// returning it returns those columns with types the fields can be decoded
+/// from.
+///
+/// Derive this alongside [FromRow](postgres_from_row::FromRow) using
+/// `#[derive(RowColumns)]`, which reads the same `#[from_row(...)]`
+/// attributes: `rename` changes the column name, `from` and `try_from` change
+/// the type decoded from the column, and `flatten` checks the columns of the
+/// field's type instead (which must then derive `RowColumns` too).
+///
+/// # Example
+/// ```
+/// # use postgres_from_row::FromRow;
+/// # use postgres_named_parameters::RowColumns;
+/// #[derive(FromRow, RowColumns)]
+/// struct Person {
+///     id: i32,
+///     #[from_row(rename = "first_name")]
+///     name: String,
+///     #[from_row(flatten)]
+///     address: Address,
+/// }
+///
+/// #[derive(FromRow, RowColumns)]
+/// struct Address {
+///     street: String,
+///     city: String,
+/// }
+/// ```
+pub trait RowColumns {
+    /// Check that rows with the given columns can be decoded into this type,
+    /// returning a description of each problem.
+    fn check_columns(columns: &[Column]) -> Vec<String>;
+}
+
+/// Marker for row types implementing [FromRow](postgres_from_row::FromRow).
+/// See [DecodeRow].
+pub enum Struct {}
+
+/// Marker for scalar row types implementing
+/// [FromSql](postgres::types::FromSql). See [DecodeRow].
+pub enum Scalar {}
+
+/// Marker for tuple row types. See [DecodeRow].
+pub enum Tuple {}
+
+impl<T: postgres_from_row::FromRow> DecodeRow<Struct> for T {
+    fn decode_row(row: &postgres::Row) -> Result<Self, crate::Error> {
+        T::try_from_row(row).map_err(|err| crate::Error::decode(err, None))
+    }
+}
+
+impl<T: FromSqlOwned> DecodeRow<Scalar> for T {
+    fn decode_row(row: &postgres::Row) -> Result<Self, crate::Error> {
+        row.try_get(0).map_err(|err| decode_error(err, row, 0))
+    }
+
+    fn check_columns(columns: &[Column]) -> Vec<String> {
+        match columns.first() {
+            Some(column) => check_column::<T>(column).into_iter().collect(),
+            None => vec!["returns no columns".to_owned()],
+        }
+    }
+}
+
+macro_rules! impl_decode_row_for_tuple {
+    ($($index:tt: $element:ident),+) => {
+        impl<$($element: FromSqlOwned),+> DecodeRow<Tuple> for ($($element,)+) {
+            fn decode_row(row: &postgres::Row) -> Result<Self, crate::Error> {
+                Ok(($(row
+                    .try_get::<_, $element>($index)
+                    .map_err(|err| decode_error(err, row, $index))?,)+))
+            }
+
+            fn check_columns(columns: &[Column]) -> Vec<String> {
+                let elements = [$($index),+].len();
+                if columns.len() < elements {
+                    return vec![format!(
+                        "returns {} columns, but the row type has {} elements",
+                        columns.len(),
+                        elements,
+                    )];
+                }
+                [$(check_column::<$element>(&columns[$index])),+]
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            }
+        }
+    };
+}
+
+impl_decode_row_for_tuple!(0: A);
+impl_decode_row_for_tuple!(0: A, 1: B);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C, 3: D);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C, 3: D, 4: E);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G, 7: H);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G, 7: H, 8: I);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G, 7: H, 8: I, 9: J);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G, 7: H, 8: I, 9: J, 10: K);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G, 7: H, 8: I, 9: J, 10: K, 11: L);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G, 7: H, 8: I, 9: J, 10: K, 11: L, 12: M);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G, 7: H, 8: I, 9: J, 10: K, 11: L, 12: M, 13: N);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G, 7: H, 8: I, 9: J, 10: K, 11: L, 12: M, 13: N, 14: O);
+impl_decode_row_for_tuple!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G, 7: H, 8: I, 9: J, 10: K, 11: L, 12: M, 13: N, 14: O, 15: P);
+
+/// Create an error for the column at the given index which could not be
+/// decoded.
+fn decode_error(source: postgres::Error, row: &postgres::Row, index: usize) -> crate::Error {
+    crate::Error::decode(source, row.columns().get(index).map(Column::name))
+}
+
+/// Check that the given column can be decoded into `T`.
+pub(crate) fn check_column<T: FromSqlOwned>(column: &Column) -> Option<String> {
+    if <T as FromSql>::accepts(column.type_()) {
+        None
+    } else {
+        Some(format!(
+            "column `{}` has type {}, which can't be decoded into `{}`",
+            column.name(),
+            column.type_(),
+            std::any::type_name::<T>(),
+        ))
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{Connection, Explain, ExplainOptions};
+use std::path::Path;
+
+/// Assert that the shape of a statement or query's plan (see [Plan::shape](crate::Plan::shape))
+/// matches the snapshot saved in `directory/name.plan`, so that a migration or
+/// data change which makes Postgres pick a worse plan (e.g. a `Seq Scan`
+/// instead of an `Index Scan`) fails your tests.
+///
+/// This is meant to be called from tests, against a local database seeded
+/// with realistic data (Postgres picks different plans for tiny tables). The
+/// plan is found using `EXPLAIN` without `ANALYZE`, so the statement or query
+/// isn't run.
+///
+/// If the snapshot doesn't exist yet, it is written and the assertion passes.
+/// To accept a new plan, delete the snapshot or re-run the test with the
+/// environment variable `PLAN_SNAPSHOTS=overwrite`, and review the change to
+/// the snapshot like any other change.
+///
+/// # Panics
+/// Panics if the plan doesn't match the snapshot, or if the plan or snapshot
+/// can't be read.
+///
+/// # Example
+/// ```no_run
+/// # use postgres_from_row::FromRow;
+/// # use postgres_named_parameters::{assert_plan_snapshot, Query};
+/// # #[derive(FromRow, Debug)]
+/// # struct Person {
+/// #     first_name: String,
+/// # }
+/// #[derive(Query)]
+/// #[query(sql = "SELECT * FROM Person WHERE id = @id", row = Person)]
+/// struct GetPerson {
+///     id: i32,
+/// }
+///
+/// #[test]
+/// fn get_person_uses_index() {
+///     let mut db = postgres::Client::connect("host=localhost user=postgres", postgres::NoTls)
+///         .unwrap();
+///     assert_plan_snapshot(&mut db, "tests/plans", "get_person", &GetPerson { id: 123 });
+/// }
+/// ```
+#[track_caller]
+pub fn assert_plan_snapshot<E: Explain>(
+    connection: &mut impl Connection<E::Requires>,
+    directory: impl AsRef<Path>,
+    name: &str,
+    statement_or_query: &E,
+) {
+    let shape = match statement_or_query.explain(connection, ExplainOptions::default()) {
+        Ok(plan) => plan.shape(),
+        Err(err) => panic!("Failed to get the plan for {}: {}", name, err),
+    };
+    let path = directory.as_ref().join(format!("{}.plan", name));
+    let overwrite = std::env::var("PLAN_SNAPSHOTS").is_ok_and(|value| value == "overwrite");
+    if overwrite || !path.exists() {
+        if let Err(err) = std::fs::create_dir_all(directory.as_ref())
+            .and_then(|()| std::fs::write(&path, &shape))
+        {
+            panic!("Failed to write plan snapshot {}: {}", path.display(), err);
+        }
+        return;
+    }
+    let snapshot = match std::fs::read_to_string(&path) {
+        Ok(snapshot) => snapshot,
+        Err(err) => panic!("Failed to read plan snapshot {}: {}", path.display(), err),
+    };
+    if snapshot != shape {
+        panic!(
+            "The plan for {} doesn't match the snapshot {}\n\nExpected:\n{}\nActual:\n{}\n\
+            If the new plan is expected, re-run with PLAN_SNAPSHOTS=overwrite to update the snapshot.",
+            name,
+            path.display(),
+            snapshot,
+            shape,
+        );
+    }
+}
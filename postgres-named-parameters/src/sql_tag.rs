@@ -0,0 +1,127 @@
+use std::borrow::Cow;
+use std::sync::RwLock;
+
+/// A function returning extra tags to add to the SQL comment of statements
+/// and queries which use the `tag` parameter. See [set_sql_tag_hook].
+pub type SqlTagHook = fn() -> Vec<(&'static str, String)>;
+
+static HOOK: RwLock<Option<SqlTagHook>> = RwLock::new(None);
+
+/// Add extra tags (e.g. a request ID) to the comment which the `tag` parameter
+/// (or the `sql-tags` feature) appends to the SQL of statements and queries.
+///
+/// The hook is called each time a tagged statement or query is run, and
+/// returns `(key, value)` pairs which are added to the comment, e.g.
+/// `/* query='GetPeople',file='src/db.rs',line=42,request_id='abc123' */`. Quotes,
+/// backslashes, `*` and `%` in the keys and values are percent-encoded (e.g.
+/// `'` becomes `%27`), so that they can't end the quoted value or start or
+/// end a comment. Calling this again replaces the previous hook.
+///
+/// # Example
+/// ```
+/// thread_local! {
+///     static REQUEST_ID: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+/// }
+///
+/// postgres_named_parameters::set_sql_tag_hook(|| {
+///     REQUEST_ID.with(|id| match &*id.borrow() {
+///         Some(id) => vec![("request_id", id.clone())],
+///         None => vec![],
+///     })
+/// });
+/// ```
+pub fn set_sql_tag_hook(hook: SqlTagHook) {
+    *HOOK.write().unwrap_or_else(|err| err.into_inner()) = Some(hook);
+}
+
+/// Remove the hook installed using [set_sql_tag_hook], if any.
+pub fn clear_sql_tag_hook() {
+    *HOOK.write().unwrap_or_else(|err| err.into_inner()) = None;
+}
+
+/// Add the tags returned by the hook installed using [set_sql_tag_hook] (if
+/// any) to the comment which `#[derive(Query)]` and `#[derive(Statement)]`
+/// appended to the SQL. Used by the derive macros when the `tag` parameter is
+/// provided.
+pub fn with_runtime_tags(sql: &'static str) -> Cow<'static, str> {
+    let Some(hook) = *HOOK.read().unwrap_or_else(|err| err.into_inner()) else {
+        return Cow::Borrowed(sql);
+    };
+    let tags = hook();
+    // The comment may be nested inside a wrapping query (e.g. for
+    // `query_exists`), so look for it rather than assuming it's at the end
+    let end_of_comment = sql
+        .rfind("\n/* query='")
+        .and_then(|start| sql[start..].find(" */").map(|end| start + end));
+    let (Some(end_of_comment), false) = (end_of_comment, tags.is_empty()) else {
+        return Cow::Borrowed(sql);
+    };
+    let mut tagged = sql[..end_of_comment].to_owned();
+    for (key, value) in tags {
+        tagged.push_str(&format!(",{}='{}'", escape(key), escape(&value)));
+    }
+    tagged.push_str(&sql[end_of_comment..]);
+    Cow::Owned(tagged)
+}
+
+/// Percent-encode the characters of a tag key or value which could end the
+/// quoted value or start or end a comment (Postgres comments nest, so an
+/// unmatched `/*` would swallow the rest of the SQL).
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '%' => escaped.push_str("%25"),
+            '\'' => escaped.push_str("%27"),
+            '*' => escaped.push_str("%2A"),
+            '\\' => escaped.push_str("%5C"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_leaves_plain_values_alone() {
+        assert_eq!(escape("abc-123"), "abc-123");
+        assert_eq!(escape("src/db.rs"), "src/db.rs");
+    }
+
+    #[test]
+    fn escape_encodes_quotes_backslashes_and_comments() {
+        assert_eq!(escape("it's"), "it%27s");
+        assert_eq!(escape(r"a\b"), "a%5Cb");
+        assert_eq!(escape("*/ DROP TABLE Person; /*"), "%2A/ DROP TABLE Person; /%2A");
+        assert_eq!(escape("50%"), "50%25");
+    }
+
+    // The hook is global, so everything using it is in one test
+    #[test]
+    fn with_runtime_tags_adds_escaped_tags() {
+        const SQL: &str = "SELECT 1\n/* query='GetOne',file='src/db.rs',line=3 */";
+        const NESTED: &str =
+            "SELECT EXISTS (SELECT 1\n/* query='GetOne',file='src/db.rs',line=3 */\n)";
+
+        clear_sql_tag_hook();
+        assert_eq!(with_runtime_tags(SQL), SQL);
+
+        set_sql_tag_hook(|| vec![("request_id", "a'b/*c".to_owned())]);
+        assert_eq!(
+            with_runtime_tags(SQL),
+            "SELECT 1\n/* query='GetOne',file='src/db.rs',line=3,request_id='a%27b/%2Ac' */"
+        );
+        assert_eq!(
+            with_runtime_tags(NESTED),
+            "SELECT EXISTS (SELECT 1\n/* query='GetOne',file='src/db.rs',line=3,request_id='a%27b/%2Ac' */\n)"
+        );
+        assert_eq!(with_runtime_tags("SELECT 1"), "SELECT 1");
+
+        set_sql_tag_hook(Vec::new);
+        assert_eq!(with_runtime_tags(SQL), SQL);
+        clear_sql_tag_hook();
+    }
+}
@@ -0,0 +1,77 @@
+use crate::Error;
+use postgres::error::SqlState;
+
+/// Check whether a [postgres::Error] was caused by a query or statement
+/// exceeding its `timeout` (i.e. Postgres's `statement_timeout`).
+///
+/// The statements and queries in this crate return an [Error](crate::Error)
+/// instead, whose [kind](crate::Error::kind) is already
+/// [Timeout](crate::ErrorKind::Timeout) in this case. This function is useful
+/// when you only have a [postgres::Error], e.g. when running SQL using
+/// `postgres` directly.
+///
+/// # Example
+/// ```no_run
+/// fn main() -> Result<(), postgres::Error> {
+///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///         .unwrap_or("host=localhost user=postgres".to_owned());
+///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
+///
+///     db.batch_execute("SET statement_timeout = '2s'")?;
+///     match db.query("SELECT * FROM Person", &[]) {
+///         Ok(people) => println!("Found {} people", people.len()),
+///         Err(err) if postgres_named_parameters::is_timeout(&err) => {
+///             println!("Gave up on finding people after 2 seconds")
+///         }
+///         Err(err) => return Err(err),
+///     }
+///     Ok(())
+/// }
+/// ```
+///
+/// Postgres uses the same SQLSTATE code (`57014`) when a query is cancelled
+/// for other reasons, e.g. by `pg_cancel_backend`, and the message saying why
+/// depends on the server's locale. So this returns `true` for any cancelled
+/// query.
+pub fn is_timeout(error: &postgres::Error) -> bool {
+    error.code() == Some(&SqlState::QUERY_CANCELED)
+}
+
+/// Run `f` inside a transaction (or a savepoint, if the connection is already
+/// a transaction) whose statement timeout is set to the given number of
+/// milliseconds. Used by `#[derive(Query)]` and `#[derive(Statement)]` when the
+/// `timeout` parameter is provided.
+///
+/// Any query `f` runs which is cancelled is reported as a
+/// [Timeout](crate::ErrorKind::Timeout), since the timeout is the reason
+/// queries are cancelled while it is set.
+pub fn with_statement_timeout<T>(
+    connection: &mut impl postgres::GenericClient,
+    timeout_milliseconds: u64,
+    f: impl FnOnce(&mut postgres::Transaction<'_>) -> Result<T, Error>,
+) -> Result<T, Error> {
+    let mut transaction = connection.transaction()?;
+    let messages = transaction.simple_query(&format!(
+        "SELECT current_setting('statement_timeout'); SET LOCAL statement_timeout = {}",
+        timeout_milliseconds
+    ))?;
+    let previous_timeout = messages
+        .iter()
+        .find_map(|message| match message {
+            postgres::SimpleQueryMessage::Row(row) => row.get(0).map(str::to_owned),
+            _ => None,
+        })
+        .unwrap_or_else(|| "0".to_owned());
+
+    let result = f(&mut transaction).map_err(Error::timed_out)?;
+
+    // SET LOCAL lasts until the end of the top-level transaction, so if we are
+    // in a savepoint, restore the previous timeout for the rest of the
+    // enclosing transaction
+    transaction.batch_execute(&format!(
+        "SELECT set_config('statement_timeout', '{}', true)",
+        previous_timeout.replace('\'', "''")
+    ))?;
+    transaction.commit()?;
+    Ok(result)
+}
@@ -0,0 +1,289 @@
+use postgres::error::SqlState;
+use std::time::Duration;
+
+/// Options for [run_in_transaction].
+///
+/// Use [Default] to fill in the options you don't care about:
+/// ```
+/// # use postgres_named_parameters::TransactionOptions;
+/// let options = TransactionOptions {
+///     isolation_level: Some(postgres::IsolationLevel::Serializable),
+///     max_retries: 5,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone)]
+pub struct TransactionOptions {
+    /// The isolation level of the transaction, or `None` to use the database's
+    /// default (normally `READ COMMITTED`).
+    pub isolation_level: Option<postgres::IsolationLevel>,
+    /// Whether the transaction is `READ ONLY`.
+    pub read_only: bool,
+    /// Whether the transaction is `DEFERRABLE`. This only has an effect for
+    /// `SERIALIZABLE READ ONLY` transactions.
+    pub deferrable: bool,
+    /// How many times to re-run the transaction after it fails due to a
+    /// serialization failure or a deadlock. Defaults to 3.
+    pub max_retries: u32,
+    /// How long to wait before the first retry. The wait is doubled for each
+    /// subsequent retry. Defaults to 10 milliseconds.
+    pub backoff: Duration,
+}
+
+impl Default for TransactionOptions {
+    fn default() -> Self {
+        TransactionOptions {
+            isolation_level: None,
+            read_only: false,
+            deferrable: false,
+            max_retries: 3,
+            backoff: Duration::from_millis(10),
+        }
+    }
+}
+
+/// Run `f` inside a transaction and commit it, re-running `f` in a new
+/// transaction if it fails due to a serialization failure (SQLSTATE `40001`)
+/// or a deadlock (SQLSTATE `40P01`).
+///
+/// If `f` returns any other error, or still fails after
+/// [max_retries](TransactionOptions::max_retries) retries, the transaction is
+/// rolled back and the error is returned. Because `f` may be run more than
+/// once, it should not have side effects outside of the transaction.
+///
+/// `f` can return any error type which implements [RetryableError], such as
+/// [Error](crate::Error), [StatementError](crate::StatementError) or
+/// [postgres::Error]. Implement it for your own error type to decide which of
+/// its errors are retried.
+///
+/// # Example
+/// ```no_run
+/// # use postgres_named_parameters::{run_in_transaction, Statement, TransactionOptions};
+/// #[derive(Statement)]
+/// #[statement(sql = "UPDATE Account SET balance = balance + @amount WHERE id = @id")]
+/// struct AdjustBalance {
+///     id: i32,
+///     amount: i64,
+/// }
+///
+/// fn main() -> Result<(), postgres_named_parameters::Error> {
+///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///         .unwrap_or("host=localhost user=postgres".to_owned());
+///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
+///
+///     let options = TransactionOptions {
+///         isolation_level: Some(postgres::IsolationLevel::Serializable),
+///         ..Default::default()
+///     };
+///     run_in_transaction(&mut db, &options, |transaction| {
+///         AdjustBalance { id: 1, amount: -100 }.execute_statement(transaction)?;
+///         AdjustBalance { id: 2, amount: 100 }.execute_statement(transaction)?;
+///         Ok(())
+///     })
+/// }
+/// ```
+pub fn run_in_transaction<T, E: RetryableError>(
+    client: &mut postgres::Client,
+    options: &TransactionOptions,
+    mut f: impl FnMut(&mut postgres::Transaction<'_>) -> Result<T, E>,
+) -> Result<T, E> {
+    let mut retries = 0;
+    loop {
+        match try_transaction(client, options, &mut f) {
+            Err(err) if retries < options.max_retries && err.is_retryable() => {
+                std::thread::sleep(options.backoff.saturating_mul(1 << retries.min(16)));
+                retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn try_transaction<T, E: From<postgres::Error>>(
+    client: &mut postgres::Client,
+    options: &TransactionOptions,
+    f: &mut impl FnMut(&mut postgres::Transaction<'_>) -> Result<T, E>,
+) -> Result<T, E> {
+    let mut builder = client
+        .build_transaction()
+        .read_only(options.read_only)
+        .deferrable(options.deferrable);
+    if let Some(isolation_level) = options.isolation_level {
+        builder = builder.isolation_level(isolation_level);
+    }
+    let mut transaction = builder.start()?;
+    // If `f` fails, the transaction is rolled back when it is dropped
+    let value = f(&mut transaction)?;
+    // Serialization failures can also be reported when committing
+    transaction.commit()?;
+    Ok(value)
+}
+
+/// An error returned by the closure passed to [run_in_transaction], which
+/// says whether the transaction should be re-run.
+///
+/// # Example
+/// ```
+/// # use postgres_named_parameters::RetryableError;
+/// enum TransferError {
+///     InsufficientFunds,
+///     Db(postgres_named_parameters::Error),
+/// }
+///
+/// impl From<postgres::Error> for TransferError {
+///     fn from(error: postgres::Error) -> Self {
+///         TransferError::Db(error.into())
+///     }
+/// }
+///
+/// impl RetryableError for TransferError {
+///     fn is_retryable(&self) -> bool {
+///         match self {
+///             TransferError::InsufficientFunds => false,
+///             TransferError::Db(error) => error.is_retryable(),
+///         }
+///     }
+/// }
+/// ```
+pub trait RetryableError: From<postgres::Error> {
+    /// Whether the transaction failed in a way which re-running it may fix.
+    /// For database errors, this is a serialization failure (SQLSTATE
+    /// `40001`) or a deadlock (SQLSTATE `40P01`).
+    fn is_retryable(&self) -> bool;
+}
+
+impl RetryableError for postgres::Error {
+    fn is_retryable(&self) -> bool {
+        is_retryable_code(self.code())
+    }
+}
+
+impl RetryableError for crate::Error {
+    fn is_retryable(&self) -> bool {
+        is_retryable_code(self.code())
+    }
+}
+
+impl<E> RetryableError for crate::StatementError<E> {
+    fn is_retryable(&self) -> bool {
+        self.error().is_retryable()
+    }
+}
+
+fn is_retryable_code(code: Option<&SqlState>) -> bool {
+    matches!(
+        code,
+        Some(&SqlState::T_R_SERIALIZATION_FAILURE) | Some(&SqlState::T_R_DEADLOCK_DETECTED)
+    )
+}
+
+/// Run `f` inside a savepoint with the given name, which is released if `f`
+/// returns `Ok` and rolled back if `f` returns `Err`. This lets you try a group
+/// of statements and undo only that group, while continuing with the rest of
+/// the enclosing transaction.
+///
+/// Any error (whether from `f` or from creating or releasing the savepoint) is
+/// returned as a [SavepointError], which records the name of the savepoint.
+/// Note that the name is used as-is in the `SAVEPOINT` command, so it should be
+/// a plain SQL identifier.
+///
+/// # Example
+/// ```no_run
+/// # use postgres_named_parameters::{with_savepoint, Statement};
+/// #[derive(Statement)]
+/// #[statement(sql = "INSERT INTO Person (first_name, last_name) VALUES (@first_name, @last_name)")]
+/// struct InsertPerson<'a> {
+///     first_name: &'a str,
+///     last_name: &'a str,
+/// }
+///
+/// #[derive(Statement)]
+/// #[statement(sql = "INSERT INTO Audit (message) VALUES (@message)")]
+/// struct InsertAudit<'a> {
+///     message: &'a str,
+/// }
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let connection_string = std::env::var("POSTGRES_CONNECTION_STRING")
+///         .unwrap_or("host=localhost user=postgres".to_owned());
+///     let mut db = postgres::Client::connect(&connection_string, postgres::NoTls)?;
+///     let mut transaction = db.transaction()?;
+///
+///     let result = with_savepoint(&mut transaction, "insert_people", |savepoint| {
+///         InsertPerson { first_name: "John", last_name: "Doe" }.execute_statement(savepoint)?;
+///         InsertPerson { first_name: "Jane", last_name: "Doe" }.execute_statement(savepoint)?;
+///         Ok::<_, postgres_named_parameters::Error>(())
+///     });
+///     if let Err(err) = result {
+///         // Neither person was inserted, but we can carry on with the transaction
+///         InsertAudit { message: &err.to_string() }.execute_statement(&mut transaction)?;
+///     }
+///
+///     transaction.commit()?;
+///     Ok(())
+/// }
+/// ```
+pub fn with_savepoint<T, E: From<postgres::Error>>(
+    transaction: &mut postgres::Transaction<'_>,
+    name: &str,
+    f: impl FnOnce(&mut postgres::Transaction<'_>) -> Result<T, E>,
+) -> Result<T, SavepointError<E>> {
+    let savepoint_error = |error: E| SavepointError {
+        name: name.to_owned(),
+        error,
+    };
+    let mut savepoint = transaction
+        .savepoint(name)
+        .map_err(|err| savepoint_error(err.into()))?;
+    match f(&mut savepoint) {
+        Ok(value) => {
+            savepoint
+                .commit()
+                .map_err(|err| savepoint_error(err.into()))?;
+            Ok(value)
+        }
+        Err(err) => {
+            // Report the original error even if rolling back fails, since the
+            // enclosing transaction will be unusable in that case anyway
+            let _ = savepoint.rollback();
+            Err(savepoint_error(err))
+        }
+    }
+}
+
+/// An error which occurred inside a savepoint created by [with_savepoint].
+#[derive(Debug)]
+pub struct SavepointError<E> {
+    name: String,
+    error: E,
+}
+
+impl<E> SavepointError<E> {
+    /// The name of the savepoint in which the error occurred.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The error which occurred inside the savepoint.
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Consume this error and return the error which occurred inside the
+    /// savepoint.
+    pub fn into_error(self) -> E {
+        self.error
+    }
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for SavepointError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error in savepoint \"{}\": {}", self.name, self.error)
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for SavepointError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
@@ -0,0 +1,288 @@
+//! Checks that the code generated by the derive macros compiles for each of
+//! their optional parameters. The expand tests in
+//! `postgres-named-parameters-derive` only compare the generated code with a
+//! snapshot, so they don't catch generated code which doesn't type check.
+//!
+//! None of these functions need a database: it's enough that they compile.
+#![allow(dead_code)]
+
+use postgres_from_row::FromRow;
+use postgres_named_parameters::connection::AnyConnection;
+use postgres_named_parameters::postgres::GenericClient;
+use postgres_named_parameters::{
+    BulkInsert, Connection, Error, Explain, ExplainOptions, KeysetCursor, OffsetCursor, Page,
+    PaginatedQuery, Plan, Query, QueryMeta, ReturningStatement, RowColumns, Statement,
+    StatementError, Transaction, sql_fingerprint,
+};
+
+#[derive(Query)]
+#[query(sql = "SELECT count(*) FROM Person WHERE alive = @alive", row = i64, read_only)]
+struct CountPeopleReadOnly {
+    alive: bool,
+}
+
+#[derive(Query)]
+#[query(sql = "SELECT count(*) FROM Person WHERE alive = @alive", row = i64, timeout = "2s")]
+struct CountPeopleWithTimeout {
+    alive: bool,
+}
+
+#[derive(Query)]
+#[query(
+    sql = "SELECT count(*) FROM Person WHERE alive = @alive",
+    row = i64,
+    read_only,
+    timeout = "2s"
+)]
+struct CountPeopleReadOnlyWithTimeout {
+    alive: bool,
+}
+
+#[derive(Statement)]
+#[statement(sql = "UPDATE Person SET name = @name WHERE id = @id", expect_rows = 1)]
+struct RenamePerson<'a> {
+    id: i32,
+    name: &'a str,
+}
+
+#[derive(Statement)]
+#[statement(
+    sql = "DELETE FROM Person WHERE id = @id RETURNING name",
+    returning = String,
+    expect_rows = "0..=1",
+    timeout = "2s"
+)]
+struct DeletePerson {
+    id: i32,
+}
+
+#[derive(Query)]
+#[query(
+    sql = "SELECT id, first_name FROM Person WHERE alive = @alive",
+    row = (i32, String),
+    paginate = keyset(order_by = "first_name, id", total)
+)]
+struct GetPeople {
+    alive: bool,
+}
+
+#[derive(Query)]
+#[query(sql = "SELECT id FROM Person ORDER BY id", row = i32, paginate = offset)]
+struct GetPersonIds;
+
+#[derive(Query)]
+#[query(
+    sql = "SELECT id, first_name FROM Person",
+    row = (i32, String),
+    paginate = offset(order_by = "first_name DESC, id", total)
+)]
+struct GetPeopleByName;
+
+#[derive(FromRow, RowColumns)]
+struct Person {
+    id: i32,
+    #[from_row(rename = "first_name")]
+    name: String,
+    #[from_row(flatten)]
+    address: Address,
+}
+
+#[derive(FromRow, RowColumns)]
+struct Address {
+    #[from_row(from = "String")]
+    city: Box<str>,
+}
+
+#[derive(Query)]
+#[query(sql = "SELECT id, first_name, city FROM Person WHERE id = @id", row = Person)]
+struct GetPerson {
+    id: i32,
+}
+
+#[derive(Query)]
+#[query(
+    sql = "SELECT balance FROM Account WHERE id = @id FOR UPDATE",
+    row = i64,
+    requires_transaction
+)]
+struct LockAccount {
+    id: i32,
+}
+
+#[derive(Debug, PartialEq)]
+enum PersonError {
+    EmailTaken,
+}
+
+#[derive(Statement)]
+#[statement(
+    sql = "INSERT INTO Person (email) VALUES (@email) RETURNING id",
+    returning = i32,
+    on_constraint("person_email_key" => PersonError::EmailTaken)
+)]
+struct InsertPerson<'a> {
+    email: &'a str,
+}
+
+#[derive(Transaction)]
+#[transaction(error = StatementError<PersonError>)]
+struct AddPerson<'a> {
+    #[transaction(execute_returning_one)]
+    person: InsertPerson<'a>,
+    rename: RenamePerson<'a>,
+}
+
+fn count_people_read_only(
+    connection: &mut impl Connection<AnyConnection>,
+) -> Result<i64, Error> {
+    CountPeopleReadOnly { alive: true }.query_one(connection)
+}
+
+fn count_people_with_timeout(
+    connection: &mut impl Connection<AnyConnection>,
+) -> Result<i64, Error> {
+    CountPeopleWithTimeout { alive: true }.query_one(connection)?;
+    CountPeopleReadOnlyWithTimeout { alive: true }.query_one(connection)
+}
+
+fn rename_and_delete(
+    connection: &mut impl Connection<AnyConnection>,
+) -> Result<Vec<String>, Error> {
+    RenamePerson { id: 1, name: "Jane" }.execute_statement(connection)?;
+    Ok(DeletePerson { id: 1 }.execute_returning_all(connection)?.rows)
+}
+
+fn get_people(
+    connection: &mut impl Connection<AnyConnection>,
+    cursor: Option<&KeysetCursor>,
+) -> Result<Page<(i32, String), KeysetCursor>, Error> {
+    GetPeople { alive: true }.query_page(connection, cursor, 50)
+}
+
+fn get_person_ids(
+    connection: &mut impl Connection<AnyConnection>,
+    cursor: Option<&OffsetCursor>,
+) -> Result<Page<i32, OffsetCursor>, Error> {
+    GetPersonIds.query_page(connection, cursor, 50)
+}
+
+fn get_people_by_name(
+    connection: &mut impl Connection<AnyConnection>,
+    cursor: Option<&OffsetCursor>,
+) -> Result<Page<(i32, String), OffsetCursor>, Error> {
+    GetPeopleByName.query_page(connection, cursor, 50)
+}
+
+fn get_people_with_token(
+    connection: &mut impl Connection<AnyConnection>,
+    token: Option<&str>,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let cursor = token.map(KeysetCursor::from_token).transpose()?;
+    let page = GetPeople { alive: true }.query_page(connection, cursor.as_ref(), 50)?;
+    Ok(page.next_cursor.map(|cursor| cursor.to_token()))
+}
+
+fn count_people_generic(connection: &mut impl GenericClient) -> Result<i64, Error> {
+    CountPeopleWithTimeout { alive: true }.query_one(connection)?;
+    CountPeopleReadOnly { alive: true }.query_one(connection)
+}
+
+// The crate's errors still convert into postgres::Error
+fn rename_person_with_postgres_error(
+    connection: &mut impl Connection<AnyConnection>,
+) -> Result<u64, postgres::Error> {
+    CountPeopleReadOnly { alive: true }.query_one(connection)?;
+    Ok(RenamePerson { id: 1, name: "Jane" }.execute_statement(connection)?)
+}
+
+// Passing a Client instead is a compile error (see tests/ui)
+fn lock_account(transaction: &mut postgres::Transaction) -> Result<i64, Error> {
+    LockAccount { id: 1 }.query_one(transaction)
+}
+
+// Constraint violations are still classified when run as part of a transaction
+fn add_person(connection: &mut impl GenericClient) -> Result<bool, Error> {
+    let transaction = AddPerson {
+        person: InsertPerson { email: "jane@example.com" },
+        rename: RenamePerson { id: 1, name: "Jane" },
+    };
+    match transaction.execute_transaction(connection) {
+        Ok(_) => Ok(true),
+        Err(error) if error.violation() == Some(&PersonError::EmailTaken) => Ok(false),
+        Err(error) => Err(error.into()),
+    }
+}
+
+#[derive(BulkInsert)]
+#[bulk_insert(table = "Account")]
+struct Account {
+    username: String,
+    #[bulk_insert(redact)]
+    password_hash: String,
+}
+
+fn insert_accounts(connection: &mut impl GenericClient, accounts: &[Account]) -> Result<u64, Error> {
+    Account::insert_many(connection, accounts)
+}
+
+fn explain_generic(connection: &mut impl GenericClient) -> Result<Plan, Error> {
+    RenamePerson { id: 1, name: "Jane" }.explain(connection, ExplainOptions::default())
+}
+
+#[test]
+fn generated_code_compiles() {
+    // Run by `cargo test` so that this file is compiled along with the tests
+    assert_eq!(GetPersonIds::NAME, "GetPersonIds");
+}
+
+#[test]
+fn fingerprints_match_sql_fingerprint() {
+    // The fingerprint of the SQL sent to the database (e.g. as found in
+    // `pg_stat_statements`) must match the derived fingerprint
+    assert_eq!(
+        CountPeopleReadOnly::FINGERPRINT,
+        sql_fingerprint(CountPeopleReadOnly::NUMBERED_SQL)
+    );
+    assert_eq!(RenamePerson::FINGERPRINT, sql_fingerprint(RenamePerson::NUMBERED_SQL));
+    assert_eq!(
+        CountPeopleReadOnly::FINGERPRINT,
+        sql_fingerprint("SELECT count(*)\n  FROM Person\n  WHERE alive = $1;")
+    );
+    // As must the fingerprints of the SQL wrapping it
+    assert_eq!(
+        GetPersonIds::EXISTS_FINGERPRINT,
+        sql_fingerprint("SELECT EXISTS (SELECT id FROM Person ORDER BY id )")
+    );
+    assert_eq!(
+        GetPersonIds::COUNT_FINGERPRINT,
+        sql_fingerprint("SELECT count(*) FROM (SELECT id FROM Person ORDER BY id ) _")
+    );
+    assert_eq!(
+        GetPersonIds::PAGE_FINGERPRINTS,
+        [sql_fingerprint("SELECT * FROM (SELECT id FROM Person ORDER BY id ) _page LIMIT $1 OFFSET $2")]
+    );
+    assert_eq!(
+        GetPeople::PAGE_FINGERPRINTS,
+        [
+            sql_fingerprint(
+                "SELECT * FROM (SELECT id, first_name FROM Person WHERE alive = $1 ) _page ORDER BY first_name, id LIMIT $2"
+            ),
+            sql_fingerprint(
+                "SELECT * FROM (SELECT id, first_name FROM Person WHERE alive = $1 ) _page WHERE (first_name, id) > ($2, $3) ORDER BY first_name, id LIMIT $4"
+            ),
+        ]
+    );
+}
+
+#[cfg(feature = "registry")]
+#[test]
+fn sql_catalog_lists_each_query() {
+    let catalog = postgres_named_parameters::sql_catalog();
+    let entry = format!(
+        "-- name: CountPeopleReadOnly\n-- location: {}:{}\n-- fingerprint: {:016x}\n",
+        CountPeopleReadOnly::LOCATION.file(),
+        CountPeopleReadOnly::LOCATION.line(),
+        CountPeopleReadOnly::FINGERPRINT,
+    );
+    assert!(catalog.contains(&entry), "{}", catalog);
+}
@@ -0,0 +1,8 @@
+//! Checks the compiler errors for code which the derive macros are meant to
+//! reject. Run with `TRYBUILD=overwrite` to update the expected errors.
+
+#[test]
+fn ui() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/ui/*.rs");
+}
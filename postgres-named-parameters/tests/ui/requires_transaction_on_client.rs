@@ -0,0 +1,18 @@
+// A query which requires a transaction can't be run on a Client
+use postgres_named_parameters::Query;
+
+#[derive(Query)]
+#[query(
+    sql = "SELECT balance FROM Account WHERE id = @id FOR UPDATE",
+    row = i64,
+    requires_transaction
+)]
+struct LockAccount {
+    id: i32,
+}
+
+fn lock_account(client: &mut postgres::Client) -> Result<i64, postgres_named_parameters::Error> {
+    LockAccount { id: 1 }.query_one(client)
+}
+
+fn main() {}